@@ -1,34 +1,59 @@
+use crate::dirty::*;
 use crate::*;
 use embedded_graphics_core::pixelcolor::*;
 use embedded_graphics_core::prelude::*;
 use embedded_graphics_core::primitives::*;
 
-pub struct AlphaCanvas<'a, C: RgbColor, const N: usize> {
-    buffer: &'a mut Framebuffer<C, N>,
+pub struct AlphaCanvas<'a, C: PixelColor, const N: usize, B = [C; N]> {
+    buffer: &'a mut Framebuffer<C, N, B>,
+    /// Running total of blended pixels, when the owner keeps [`FrameStats`].
+    blended: Option<&'a mut u32>,
 }
 
-impl<'a, C: RgbColor, const N: usize> AlphaCanvas<'a, C, N>
+impl<'a, C: PixelColor, const N: usize, B> AlphaCanvas<'a, C, N, B>
 where
+    B: PixelStorage<C>,
     Rgba<C>: Blend<C>,
 {
     #[inline(always)]
-    pub fn new(buffer: &'a mut Framebuffer<C, N>) -> Self {
-        Self { buffer }
+    pub fn new(buffer: &'a mut Framebuffer<C, N, B>) -> Self {
+        Self {
+            buffer,
+            blended: None,
+        }
+    }
+
+    /// Like [`new`](Self::new), adding every blended pixel to `blended`.
+    #[inline(always)]
+    pub(crate) fn counted(buffer: &'a mut Framebuffer<C, N, B>, blended: &'a mut u32) -> Self {
+        Self {
+            buffer,
+            blended: Some(blended),
+        }
+    }
+
+    #[inline(always)]
+    fn count(&mut self, pixels: u32) {
+        if let Some(total) = &mut self.blended {
+            **total += pixels;
+        }
     }
 }
 
-impl<'a, C: RgbColor, const N: usize> OriginDimensions for AlphaCanvas<'a, C, N>
+impl<'a, C: PixelColor, const N: usize, B> Dimensions for AlphaCanvas<'a, C, N, B>
 where
+    B: PixelStorage<C>,
     Rgba<C>: Blend<C>,
 {
     #[inline(always)]
-    fn size(&self) -> Size {
-        self.buffer.size()
+    fn bounding_box(&self) -> Rectangle {
+        self.buffer.bounding_box()
     }
 }
 
-impl<'a, C: RgbColor, const N: usize> DrawTarget for AlphaCanvas<'a, C, N>
+impl<'a, C: PixelColor, const N: usize, B> DrawTarget for AlphaCanvas<'a, C, N, B>
 where
+    B: PixelStorage<C>,
     Rgba<C>: Blend<C>,
 {
     type Error = core::convert::Infallible;
@@ -39,20 +64,18 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        let w_u32 = self.buffer.width as u32;
-        let h_u32 = self.buffer.height as u32;
-        let w = self.buffer.width;
-        let buf = self.buffer.buf_mut();
-
+        let mut touched = Touched::new();
+        let mut count = 0;
         for Pixel(p, fg) in pixels {
-            let x = p.x as u32;
-            let y = p.y as u32;
-            if x < w_u32 && y < h_u32 {
-                let idx = (y * w + x) as usize;
-                let bg = buf[idx];
-                buf[idx] = fg.blend(bg);
+            if let Some(idx) = self.buffer.index_of(p) {
+                let buf = self.buffer.storage_mut();
+                buf.set(idx, fg.blend(buf.get(idx)));
+                touched.add(p);
+                count += 1;
             }
         }
+        self.buffer.mark_dirty(&touched.area());
+        self.count(count);
         Ok(())
     }
 
@@ -75,8 +98,11 @@ where
             return Ok(());
         }
 
-        let w = self.buffer.width;
-        let buf = self.buffer.buf_mut();
+        self.buffer.mark_dirty(&clipped);
+        self.count(clipped.size.width * clipped.size.height);
+        let stride = self.buffer.stride() as usize;
+        let origin = self.buffer.origin();
+        let buf = self.buffer.storage_mut();
 
         // Horizontal consumption counts relative to original area.
         let left_out = (clipped.top_left.x - area.top_left.x).max(0) as usize;
@@ -88,7 +114,7 @@ where
         let y1 = y0 + area.size.height as i32;
         let cy0 = clipped.top_left.y;
         let cy1 = cy0 + clipped.size.height as i32;
-        let cx0 = clipped.top_left.x as usize;
+        let cx0 = (clipped.top_left.x - origin.x) as usize;
 
         for y in y0..y1 {
             // Discard left part outside framebuffer
@@ -97,14 +123,11 @@ where
             }
 
             if y >= cy0 && y < cy1 {
-                let row_start = (y as usize) * w as usize + cx0;
-                for dst in &mut buf[row_start..row_start + mid_w] {
-                    if let Some(fg) = it.next() {
-                        *dst = fg.blend(*dst);
-                    } else {
-                        break;
-                    }
-                }
+                let row_start = ((y - origin.y) as usize) * stride + cx0;
+                buf.update(row_start..row_start + mid_w, |bg| match it.next() {
+                    Some(fg) => fg.blend(bg),
+                    None => bg,
+                });
             } else {
                 // Row is fully outside vertically: still consume the inside span.
                 for _ in 0..mid_w {
@@ -132,32 +155,27 @@ where
             return Ok(());
         }
 
-        let w = self.buffer.width;
-        let buf = self.buffer.buf_mut();
+        self.buffer.mark_dirty(&clipped);
+        self.count(clipped.size.width * clipped.size.height);
+        let stride = self.buffer.stride() as usize;
+        let origin = self.buffer.origin();
+        let buf = self.buffer.storage_mut();
 
-        let x0 = clipped.top_left.x as usize;
-        let y0 = clipped.top_left.y as usize;
+        let x0 = (clipped.top_left.x - origin.x) as usize;
+        let y0 = (clipped.top_left.y - origin.y) as usize;
         let w_span = clipped.size.width as usize;
         let y_end = y0 + clipped.size.height as usize;
 
         for y in y0..y_end {
-            let row = y * w as usize;
-            for px in &mut buf[row + x0..row + x0 + w_span] {
-                *px = color.blend(*px);
-            }
+            let row = y * stride;
+            buf.update(row + x0..row + x0 + w_span, |bg| color.blend(bg));
         }
         Ok(())
     }
 
     #[inline(always)]
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        if color.a() == 0 {
-            return Ok(());
-        }
-
-        for px in self.buffer.buf_mut().iter_mut() {
-            *px = color.blend(*px);
-        }
-        Ok(())
+        let area = self.buffer.bounding_box();
+        self.fill_solid(&area, color)
     }
 }
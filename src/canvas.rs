@@ -1,45 +1,939 @@
 pub use crate::*;
 
+use core::borrow::BorrowMut;
 use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::task::Poll;
 use embedded_graphics_core::Pixel;
 use embedded_graphics_core::{prelude::*, primitives::*};
 
 pub trait BufferStrategy: DrawTarget {
-    fn flush<T: DrawTarget<Color = Self::Color>>(&mut self, target: &mut T)
-    -> Result<(), T::Error>;
+    /// Send what changed since the previous flush through `out`.
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>;
+
+    /// Send what changed since the previous flush straight to `target`.
+    fn flush<T>(&mut self, target: &mut T) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        let frame = self.bounding_box();
+        self.flush_to(&mut Output::new(target, frame))
+    }
+
+    /// Part of the drawing that reaches the target, in draw coordinates.
+    fn visible(&self) -> Rectangle {
+        self.bounding_box()
+    }
+}
+
+/// Strategies whose flush can be split across several calls.
+pub trait StepFlush: BufferStrategy {
+    /// Send up to `max_pixels` of the frame being flushed, in whole rows, and report whether the
+    /// frame is complete. A new frame starts when none is in progress; at least one row is sent
+    /// per call.
+    fn flush_step_to<T>(
+        &mut self,
+        out: &mut Output<'_, T>,
+        max_pixels: u32,
+    ) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>;
 }
 
 pub trait HasFramebuffer<C, const N: usize>
+where
+    C: PixelColor,
+{
+    type Storage: PixelStorage<C>;
+
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, Self::Storage>;
+}
+
+/// How [`DoubleBuffer`] narrows dirty areas down to the pixels that actually changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffPolicy {
+    /// Send dirty areas as drawn, without comparing against the reference.
+    Off,
+    /// Compare dirty areas row by row against the reference. Changed runs on a row separated by
+    /// at most `max_gap` unchanged pixels are sent as one span; spans touching across rows are
+    /// merged into rectangles.
+    Rows { max_gap: u32 },
+}
+
+impl DiffPolicy {
+    // A window command costs about as much as a dozen 16-bit pixels on common SPI panels.
+    const DEFAULT: Self = Self::Rows { max_gap: 16 };
+}
+
+impl Default for DiffPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Double buffering: draw into `current`, compare against `reference`, which mirrors the last
+/// flushed frame. Flush sends only the pixels that differ, as selected by the [`DiffPolicy`].
+pub struct DoubleBuffer<C, const N: usize, B = [C; N]>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    current: Framebuffer<C, N, B>,
+    reference: Framebuffer<C, N, B>,
+    policy: DiffPolicy,
+    /// Whether `reference` matches the panel; false until the first flush.
+    synced: bool,
+    cursor: FlushCursor,
+}
+
+impl<C, const N: usize> DoubleBuffer<C, N>
+where
+    C: RgbColor,
+{
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self::from_framebuffers(
+            Framebuffer::new(width, height),
+            Framebuffer::new(width, height),
+        )
+    }
+
+    /// `W` x `H` double buffer; dimensions that do not match `N` fail to compile.
+    pub const fn sized<const W: usize, const H: usize>() -> Self {
+        Self::from_framebuffers(Framebuffer::sized::<W, H>(), Framebuffer::sized::<W, H>())
+    }
+}
+
+impl<C, const N: usize, B> DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    /// Double buffer over two framebuffers of the same geometry, e.g. ones placed in external RAM.
+    pub const fn from_framebuffers(
+        current: Framebuffer<C, N, B>,
+        reference: Framebuffer<C, N, B>,
+    ) -> Self {
+        Self {
+            current,
+            reference,
+            policy: DiffPolicy::DEFAULT,
+            synced: false,
+            cursor: FlushCursor::new(),
+        }
+    }
+
+    pub fn with_diff_policy(mut self, policy: DiffPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    #[inline]
+    pub fn diff_policy(&self) -> DiffPolicy {
+        self.policy
+    }
+
+    #[inline]
+    pub fn set_diff_policy(&mut self, policy: DiffPolicy) {
+        self.policy = policy;
+    }
+
+    /// Areas that differ from the reference, as selected by the diff policy.
+    fn changed(&mut self) -> DirtyRegions {
+        let dirty = self.current.take_dirty();
+        if !self.synced {
+            return dirty;
+        }
+        let DiffPolicy::Rows { max_gap } = self.policy else {
+            return dirty;
+        };
+        let mut changed = DirtyRegions::new();
+        for area in dirty.iter() {
+            for rect in self.current.diff(&self.reference, &area, max_gap).iter() {
+                changed.add(rect);
+            }
+        }
+        changed
+    }
+}
+
+impl<C, const N: usize, B> DrawTarget for DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.current.draw_iter(pixels)
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.current.fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: C) -> Result<(), Self::Error> {
+        self.current.fill_solid(area, color)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self, color: C) -> Result<(), Self::Error> {
+        self.current.clear(color)
+    }
+}
+
+impl<C, const N: usize, B> OriginDimensions for DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    fn size(&self) -> Size {
+        self.current.size()
+    }
+}
+
+impl<C, const N: usize, B> BufferStrategy for DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        if !self.cursor.is_idle() {
+            self.flush_step_to(out, u32::MAX)?;
+        }
+        let policy = if self.synced {
+            self.policy
+        } else {
+            DiffPolicy::Off
+        };
+        let dirty = self.current.take_dirty();
+        for (i, area) in dirty.iter().enumerate() {
+            let changed = match policy {
+                DiffPolicy::Off => {
+                    let mut all = DirtyRegions::new();
+                    all.add(area);
+                    all
+                }
+                DiffPolicy::Rows { max_gap } => self.current.diff(&self.reference, &area, max_gap),
+            };
+            for rect in changed.iter() {
+                if let Err(e) = out.send(&self.current, &rect) {
+                    // Sent rects are in the reference already, so the retry diffs them away.
+                    self.current.restore_dirty(dirty.iter().skip(i));
+                    return Err(e);
+                }
+                self.reference.copy_area_from(&self.current, &rect);
+            }
+        }
+        self.synced = true;
+        Ok(())
+    }
+}
+
+impl<C, const N: usize, B> DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+    Rgba<C>: Blend<C>,
+{
+    /// Send the whole frame `progress` (0..=255) of the way from the last flushed frame to the
+    /// one drawn since, using `transition`. Call with increasing progress after drawing the new
+    /// frame; at 255 the new frame becomes the reference, as after a regular flush.
+    pub fn transition_to<T>(
+        &mut self,
+        out: &mut Output<'_, T>,
+        transition: Transition,
+        progress: u8,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = C>,
+    {
+        let frame = self.current.bounding_box();
+        let (old, new) = (&self.reference, &self.current);
+        out.send_with(&frame, |p| transition.pixel(old, new, progress, p))?;
+        if progress == u8::MAX {
+            self.current.take_dirty();
+            self.reference.copy_area_from(&self.current, &frame);
+            self.synced = true;
+        }
+        Ok(())
+    }
+}
+
+impl<C, const N: usize, B> StepFlush for DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    fn flush_step_to<T>(
+        &mut self,
+        out: &mut Output<'_, T>,
+        max_pixels: u32,
+    ) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        if self.cursor.is_idle() {
+            let changed = self.changed();
+            self.cursor.start(changed);
+            self.synced = true;
+        }
+        let (current, reference) = (&self.current, &mut self.reference);
+        self.cursor.step(max_pixels, |band| {
+            out.send(current, band)?;
+            reference.copy_area_from(current, band);
+            Ok(())
+        })
+    }
+}
+
+impl<C, const N: usize, B> HasFramebuffer<C, N> for DoubleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    type Storage = B;
+
+    #[inline(always)]
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, B> {
+        &mut self.current
+    }
+}
+
+/// Single buffering: only one framebuffer; flush pushes the areas drawn since the previous flush.
+pub struct SingleBuffer<C, const N: usize, B = [C; N]>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    current: Framebuffer<C, N, B>,
+    cursor: FlushCursor,
+}
+
+impl<C, const N: usize> SingleBuffer<C, N>
+where
+    C: RgbColor,
+{
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self::from_framebuffer(Framebuffer::new(width, height))
+    }
+
+    /// `W` x `H` single buffer; dimensions that do not match `N` fail to compile.
+    pub const fn sized<const W: usize, const H: usize>() -> Self {
+        Self::from_framebuffer(Framebuffer::sized::<W, H>())
+    }
+}
+
+impl<C, const N: usize, B> SingleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    /// Single buffer over `current`, e.g. a framebuffer placed in external RAM.
+    pub const fn from_framebuffer(current: Framebuffer<C, N, B>) -> Self {
+        Self {
+            current,
+            cursor: FlushCursor::new(),
+        }
+    }
+}
+
+impl<C, const N: usize, B> BufferStrategy for SingleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        if !self.cursor.is_idle() {
+            self.flush_step_to(out, u32::MAX)?;
+        }
+        out.send_dirty(&mut self.current)
+    }
+}
+
+impl<C, const N: usize, B> StepFlush for SingleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    fn flush_step_to<T>(
+        &mut self,
+        out: &mut Output<'_, T>,
+        max_pixels: u32,
+    ) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        if self.cursor.is_idle() {
+            let dirty = self.current.take_dirty();
+            self.cursor.start(dirty);
+        }
+        let current = &self.current;
+        self.cursor.step(max_pixels, |band| out.send(current, band))
+    }
+}
+
+impl<C, const N: usize, B> DrawTarget for SingleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.current.draw_iter(pixels)
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.current.fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: C) -> Result<(), Self::Error> {
+        self.current.fill_solid(area, color)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self, color: C) -> Result<(), Self::Error> {
+        self.current.clear(color)
+    }
+}
+
+impl<C, const N: usize, B> OriginDimensions for SingleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    fn size(&self) -> Size {
+        self.current.size()
+    }
+}
+
+impl<C, const N: usize, B> HasFramebuffer<C, N> for SingleBuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    type Storage = B;
+
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, B> {
+        &mut self.current
+    }
+}
+
+/// Triple buffering: a front buffer being sent, a ready buffer holding the latest completed frame,
+/// and a back buffer being drawn. The renderer never waits for a slow transfer: presenting a new
+/// frame while another one is still waiting replaces it, so the panel always gets the newest frame.
+///
+/// [`present`](Self::present) copies the finished frame into the next back buffer, so drawing
+/// continues from the latest frame.
+///
+/// Transfers need [`StableStorage`], e.g. framebuffers made by [`Framebuffer::init`] over
+/// `static` memory. Dropping the buffer while a transfer is in flight panics.
+pub struct TripleBuffer<C, const N: usize, B = [C; N]>
 where
     C: RgbColor,
+    B: Storage<C>,
 {
-    fn current_mut(&mut self) -> &mut Framebuffer<C, N>;
+    buffers: [Framebuffer<C, N, B>; 3],
+    front: usize,
+    back: usize,
+    ready: Option<usize>,
+    /// Areas changed by presented frames that have not been sent yet.
+    pending: DirtyRegions,
+    in_flight: bool,
+    /// Rows of the front frame that the transfer has not been started on yet.
+    rest: Rectangle,
 }
 
-/// Double buffering: draw into `current`, compare/prepare against `reference`, then swap on flush.
-pub struct DoubleBuffer<C, const N: usize>
+impl<C, const N: usize> TripleBuffer<C, N>
 where
     C: RgbColor,
 {
-    current: Framebuffer<C, N>,
-    reference: Framebuffer<C, N>,
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::from_framebuffers([
+            Framebuffer::new(width, height),
+            Framebuffer::new(width, height),
+            Framebuffer::new(width, height),
+        ])
+    }
 }
 
-impl<C, const N: usize> DoubleBuffer<C, N>
-where
-    C: RgbColor,
-{
-    pub fn new(width: u32, height: u32) -> Self {
-        Self {
-            current: Framebuffer::new(width, height),
-            reference: Framebuffer::new(width, height),
+impl<C, const N: usize, B> TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Triple buffer over three framebuffers of the same geometry; the second is drawn first.
+    pub fn from_framebuffers(mut buffers: [Framebuffer<C, N, B>; 3]) -> Self {
+        // Only the first drawn frame needs to go out in full.
+        buffers[0].take_dirty();
+        buffers[2].take_dirty();
+        Self {
+            buffers,
+            front: 0,
+            back: 1,
+            ready: None,
+            pending: DirtyRegions::new(),
+            in_flight: false,
+            rest: Rectangle::zero(),
+        }
+    }
+
+    /// Whether a presented frame is waiting to be sent.
+    #[inline]
+    pub fn has_ready(&self) -> bool {
+        self.ready.is_some()
+    }
+
+    /// Finish the frame being drawn and queue it for sending, replacing a frame still waiting.
+    pub fn present(&mut self) {
+        let frame = self.back;
+        for area in self.buffers[frame].take_dirty().iter() {
+            self.pending.add(area);
+        }
+        let next = match self.ready.replace(frame) {
+            Some(dropped) => dropped,
+            None => 3 - self.front - frame,
+        };
+        let all = self.buffers[frame].bounding_box();
+        copy_between(&mut self.buffers, next, frame, &all);
+        self.back = next;
+    }
+}
+
+impl<C, const N: usize, B> TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: StableStorage<C>,
+{
+    /// Drive the transfer: start the rows of the front frame it has not been started on yet,
+    /// then, once it finishes, start sending the ready frame.
+    ///
+    /// `Ready` when the transfer is idle and no frame is waiting. On error, the rows not sent yet
+    /// go out with the next presented frame.
+    pub fn poll_flush<X>(&mut self, transfer: &mut X) -> Poll<Result<(), X::Error>>
+    where
+        X: Transfer<C>,
+    {
+        if self.in_flight {
+            let res = match transfer.poll() {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(())) if !self.rest.is_zero_sized() => {
+                    // SAFETY: as below; `front` is still the buffer in flight.
+                    match unsafe { start_rows(transfer, &self.buffers[self.front], &self.rest) } {
+                        Ok(rest) => {
+                            self.rest = rest;
+                            return Poll::Pending;
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                Poll::Ready(res) => res,
+            };
+            self.in_flight = false;
+            let rest = core::mem::replace(&mut self.rest, Rectangle::zero());
+            if res.is_err() && !rest.is_zero_sized() {
+                self.pending.add(rest);
+            }
+            res?;
+        }
+
+        let Some(ready) = self.ready else {
+            return Poll::Ready(Ok(()));
+        };
+
+        let band = self
+            .pending
+            .iter()
+            .fold(Rectangle::zero(), |acc, r| union(&acc, &r));
+        if !band.is_zero_sized() {
+            let frame = &self.buffers[ready];
+            let rows = Rectangle::new(
+                Point::new(0, band.top_left.y),
+                Size::new(frame.width, band.size.height),
+            );
+            // SAFETY: the storage is stable, and `front` is neither drawn nor chosen by `present`
+            // until the transfer is polled to completion; dropping it while in flight panics.
+            self.rest = unsafe { start_rows(transfer, frame, &rows) }?;
+        }
+
+        self.front = ready;
+        self.ready = None;
+        self.pending.clear();
+        if band.is_zero_sized() {
+            return Poll::Ready(Ok(()));
+        }
+        self.in_flight = true;
+        Poll::Pending
+    }
+}
+
+impl<C, const N: usize, B> Drop for TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn drop(&mut self) {
+        assert!(
+            !self.in_flight,
+            "TripleBuffer dropped while a transfer is in flight"
+        );
+    }
+}
+
+impl<C, const N: usize, B> BufferStrategy for TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Present the back buffer and send it through `out` right away, bypassing any
+    /// [`Transfer`].
+    ///
+    /// Panics while a transfer is in flight: moving `front` would let [`present`](Self::present)
+    /// overwrite the buffer still being read, so drive it to completion with
+    /// [`poll_flush`](TripleBuffer::poll_flush) first.
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        assert!(!self.in_flight, "flush_to while a transfer is in flight");
+        self.present();
+        if let Some(ready) = self.ready {
+            // On error the frame stays queued with all its areas.
+            for area in self.pending.iter() {
+                out.send(&self.buffers[ready], &area)?;
+            }
+            self.front = ready;
+            self.ready = None;
+            self.pending.clear();
+        }
+        Ok(())
+    }
+}
+
+impl<C, const N: usize, B> DrawTarget for TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.buffers[self.back].draw_iter(pixels)
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.buffers[self.back].fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: C) -> Result<(), Self::Error> {
+        self.buffers[self.back].fill_solid(area, color)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self, color: C) -> Result<(), Self::Error> {
+        self.buffers[self.back].clear(color)
+    }
+}
+
+impl<C, const N: usize, B> OriginDimensions for TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn size(&self) -> Size {
+        self.buffers[self.back].size()
+    }
+}
+
+impl<C, const N: usize, B> HasFramebuffer<C, N> for TripleBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    type Storage = B;
+
+    #[inline(always)]
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, B> {
+        &mut self.buffers[self.back]
+    }
+}
+
+/// Virtual buffering: a framebuffer larger than the display, of which only the viewport is shown.
+/// Moving the viewport costs nothing but a flush of the newly visible window.
+pub struct VirtualBuffer<C, const N: usize, B = [C; N]>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    current: Framebuffer<C, N, B>,
+    viewport: Rectangle,
+    /// Whether the viewport moved since the last flush.
+    moved: bool,
+}
+
+impl<C, const N: usize> VirtualBuffer<C, N>
+where
+    C: RgbColor,
+{
+    /// A `width` x `height` surface shown through a viewport of `view` size at the top-left.
+    pub fn new(width: u32, height: u32, view: Size) -> Self {
+        Self::from_framebuffer(Framebuffer::new(width, height), view)
+    }
+}
+
+impl<C, const N: usize, B> VirtualBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Like [`VirtualBuffer::new`], over `current`, e.g. a framebuffer placed in external RAM.
+    pub fn from_framebuffer(current: Framebuffer<C, N, B>, view: Size) -> Self {
+        Self {
+            current,
+            viewport: Rectangle::new(Point::zero(), view),
+            moved: true,
+        }
+    }
+
+    /// Visible part of the surface.
+    #[inline]
+    pub fn viewport(&self) -> Rectangle {
+        self.viewport
+    }
+
+    /// Move the viewport's top-left corner, clamped to keep it inside the surface.
+    pub fn set_viewport(&mut self, top_left: Point) {
+        let max = self.current.size().saturating_sub(self.viewport.size);
+        let top_left = top_left
+            .component_max(Point::zero())
+            .component_min(Point::new(max.width as i32, max.height as i32));
+        if top_left != self.viewport.top_left {
+            self.viewport.top_left = top_left;
+            self.moved = true;
+        }
+    }
+}
+
+impl<C, const N: usize, B> BufferStrategy for VirtualBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        let mut out = out.reframe(self.viewport);
+        if self.moved {
+            out.send(&self.current, &self.viewport)?;
+            self.current.take_dirty();
+            self.moved = false;
+            return Ok(());
+        }
+        out.send_dirty(&mut self.current)
+    }
+
+    #[inline]
+    fn visible(&self) -> Rectangle {
+        self.viewport
+    }
+}
+
+impl<C, const N: usize, B> DrawTarget for VirtualBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.current.draw_iter(pixels)
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.current.fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: C) -> Result<(), Self::Error> {
+        self.current.fill_solid(area, color)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self, color: C) -> Result<(), Self::Error> {
+        self.current.clear(color)
+    }
+}
+
+impl<C, const N: usize, B> OriginDimensions for VirtualBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn size(&self) -> Size {
+        self.current.size()
+    }
+}
+
+impl<C, const N: usize, B> HasFramebuffer<C, N> for VirtualBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    type Storage = B;
+
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, B> {
+        &mut self.current
+    }
+}
+
+/// Row-hash buffering: a single framebuffer plus a hash of every row (`H` = height) as last
+/// flushed. Flush sends only the dirty rows whose hash changed, which saves most of the bandwidth
+/// of a diffing [`DoubleBuffer`] for `4 * H` bytes of RAM.
+///
+/// A hash collision (about one in 2^32 per changed row) leaves that row stale until it changes again.
+pub struct HashBuffer<C, const N: usize, const H: usize, B = [C; N]>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    current: Framebuffer<C, N, B>,
+    hashes: [u32; H],
+    /// Whether `hashes` matches the panel; false until the first flush.
+    synced: bool,
+}
+
+impl<C, const N: usize, const H: usize> HashBuffer<C, N, H>
+where
+    C: RgbColor,
+{
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::from_framebuffer(Framebuffer::new(width, height))
+    }
+}
+
+impl<C, const N: usize, const H: usize, B> HashBuffer<C, N, H, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Hash buffer over `current`, e.g. a framebuffer placed in external RAM. Panics unless `H`
+    /// is its height.
+    pub fn from_framebuffer(current: Framebuffer<C, N, B>) -> Self {
+        assert_eq!(H as u32, current.height, "H must be height");
+        Self {
+            current,
+            hashes: [0; H],
+            synced: false,
+        }
+    }
+}
+
+impl<C, const N: usize, const H: usize, B> BufferStrategy for HashBuffer<C, N, H, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        let dirty = self.current.take_dirty();
+        let mut fresh = self.hashes;
+        let mut changed = DirtyRegions::new();
+
+        for area in dirty.iter() {
+            let x = area.top_left.x;
+            let width = area.size.width;
+            let mut run: Option<i32> = None;
+            for y in area.rows() {
+                fresh[y as usize] = self.current.row_hash(y as usize);
+                let differs = !self.synced || fresh[y as usize] != self.hashes[y as usize];
+                match (differs, run) {
+                    (true, None) => run = Some(y),
+                    (false, Some(start)) => {
+                        changed.add(Rectangle::new(
+                            Point::new(x, start),
+                            Size::new(width, (y - start) as u32),
+                        ));
+                        run = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(start) = run {
+                let end = area.rows().end;
+                changed.add(Rectangle::new(
+                    Point::new(x, start),
+                    Size::new(width, (end - start) as u32),
+                ));
+            }
+        }
+
+        for rect in changed.iter() {
+            if let Err(e) = out.send(&self.current, &rect) {
+                // Hashes are left as they were, so every changed row is compared again.
+                self.current.restore_dirty(dirty.iter());
+                return Err(e);
+            }
         }
+        self.hashes = fresh;
+        self.synced = true;
+        Ok(())
     }
 }
 
-impl<C, const N: usize> DrawTarget for DoubleBuffer<C, N>
+impl<C, const N: usize, const H: usize, B> DrawTarget for HashBuffer<C, N, H, B>
 where
     C: RgbColor,
+    B: Storage<C>,
 {
     type Color = C;
     type Error = Infallible;
@@ -71,71 +965,95 @@ where
     }
 }
 
-impl<C, const N: usize> OriginDimensions for DoubleBuffer<C, N>
+impl<C, const N: usize, const H: usize, B> OriginDimensions for HashBuffer<C, N, H, B>
 where
     C: RgbColor,
+    B: Storage<C>,
 {
     fn size(&self) -> Size {
         self.current.size()
     }
 }
 
-impl<C, const N: usize> BufferStrategy for DoubleBuffer<C, N>
+impl<C, const N: usize, const H: usize, B> HasFramebuffer<C, N> for HashBuffer<C, N, H, B>
 where
     C: RgbColor,
+    B: Storage<C>,
 {
-    fn flush<T>(&mut self, target: &mut T) -> Result<(), T::Error>
-    where
-        T: DrawTarget<Color = Self::Color>,
-    {
-        target.fill_contiguous(&target.bounding_box(), self.current.iter_colors())?;
-        core::mem::swap(&mut self.reference, &mut self.current);
-        Ok(())
-    }
-}
+    type Storage = B;
 
-impl<C, const N: usize> HasFramebuffer<C, N> for DoubleBuffer<C, N>
-where
-    C: RgbColor,
-{
-    #[inline(always)]
-    fn current_mut(&mut self) -> &mut Framebuffer<C, N> {
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, B> {
         &mut self.current
     }
 }
 
-/// Single buffering: only one framebuffer; flush pushes it to the target.
-pub struct SingleBuffer<C, const N: usize>
+/// Line buffering: only a band of `N / width` rows is kept; the frame is rendered band by band
+/// and each band is pushed to the target on flush. Pixels past the band's last whole row are
+/// left unused.
+pub struct LineBuffer<C, const N: usize>
 where
     C: RgbColor,
 {
-    current: Framebuffer<C, N>,
+    band: Framebuffer<C, N>,
+    height: u32,
 }
 
-impl<C, const N: usize> SingleBuffer<C, N>
+impl<C, const N: usize> LineBuffer<C, N>
 where
     C: RgbColor,
 {
     pub fn new(width: u32, height: u32) -> Self {
+        assert!(
+            width > 0 && N as u64 >= width as u64,
+            "N must hold at least one row"
+        );
+        let rows = (N as u64 / width as u64).min(height.max(1) as u64) as u32;
         Self {
-            current: Framebuffer::new(width, height),
+            band: Framebuffer::leading(width, rows),
+            height,
         }
     }
+
+    /// Number of rows in a band.
+    #[inline]
+    pub fn rows(&self) -> u32 {
+        self.band.height
+    }
+
+    /// Number of bands needed to cover the whole frame.
+    #[inline]
+    pub fn bands(&self) -> u32 {
+        self.height.div_ceil(self.band.height)
+    }
+
+    /// Area of the frame covered by the current band.
+    #[inline]
+    pub fn band_area(&self) -> Rectangle {
+        self.band.bounding_box().intersection(&self.bounding_box())
+    }
+
+    /// Move the band to `index`; drawing outside of it is clipped until the next selection.
+    #[inline]
+    pub fn select_band(&mut self, index: u32) {
+        self.band
+            .set_origin(Point::new(0, (index * self.band.height) as i32));
+    }
 }
 
-impl<C, const N: usize> BufferStrategy for SingleBuffer<C, N>
+impl<C, const N: usize> BufferStrategy for LineBuffer<C, N>
 where
     C: RgbColor,
 {
-    fn flush<T>(&mut self, target: &mut T) -> Result<(), T::Error>
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
     where
         T: DrawTarget<Color = Self::Color>,
     {
-        target.fill_contiguous(&target.bounding_box(), self.current.iter_colors())
+        let area = self.band_area();
+        out.send(&self.band, &area)
     }
 }
 
-impl<C, const N: usize> DrawTarget for SingleBuffer<C, N>
+impl<C, const N: usize> DrawTarget for LineBuffer<C, N>
 where
     C: RgbColor,
 {
@@ -147,7 +1065,7 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        self.current.draw_iter(pixels)
+        self.band.draw_iter(pixels)
     }
 
     #[inline(always)]
@@ -155,69 +1073,277 @@ where
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        self.current.fill_contiguous(area, colors)
+        self.band.fill_contiguous(area, colors)
     }
 
     #[inline(always)]
     fn fill_solid(&mut self, area: &Rectangle, color: C) -> Result<(), Self::Error> {
-        self.current.fill_solid(area, color)
+        self.band.fill_solid(area, color)
     }
 
     #[inline(always)]
     fn clear(&mut self, color: C) -> Result<(), Self::Error> {
-        self.current.clear(color)
+        self.band.clear(color)
     }
 }
 
-impl<C, const N: usize> OriginDimensions for SingleBuffer<C, N>
+impl<C, const N: usize> OriginDimensions for LineBuffer<C, N>
 where
     C: RgbColor,
 {
     fn size(&self) -> Size {
-        self.current.size()
+        Size::new(self.band.width, self.height)
     }
 }
 
-impl<C, const N: usize> HasFramebuffer<C, N> for SingleBuffer<C, N>
+impl<C, const N: usize> HasFramebuffer<C, N> for LineBuffer<C, N>
 where
     C: RgbColor,
 {
+    type Storage = [C; N];
+
     fn current_mut(&mut self) -> &mut Framebuffer<C, N> {
-        &mut self.current
+        &mut self.band
     }
 }
 
-pub struct Canvas<'a, T, S>
+/// Draw target that buffers drawing in a [`BufferStrategy`] and flushes it to `T`.
+///
+/// The target is held through `H`: borrowed by default, or owned in an [`OwnedCanvas`].
+pub struct Canvas<'a, T, S, H = &'a mut T>
 where
     T: DrawTarget,
     S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
 {
     strategy: S,
-    target: &'a mut T,
+    target: H,
+    /// Markers for `'a` and `T`, kept apart so an owned `T` need not outlive `'a`.
+    _lifetime: PhantomData<&'a ()>,
+    _target: PhantomData<fn() -> T>,
+    /// Top-left corner of the canvas on the target.
+    origin: Point,
+    orientation: Orientation,
+    scale: u32,
+    stats: FrameStats,
+    /// Whether the next flush must run even if nothing was drawn.
+    stale: bool,
+    /// Whether a frame sent by [`flush_step`](Self::flush_step) is still in progress.
+    stepping: bool,
 }
 
+/// [`Canvas`] that owns its target; [`into_parts`](Canvas::into_parts) gives it back.
+pub type OwnedCanvas<T, S> = Canvas<'static, T, S, T>;
+
 impl<'a, T, S> Canvas<'a, T, S>
 where
     T: DrawTarget,
     S: BufferStrategy<Color = T::Color>,
 {
-    /// Construct a canvas from an explicit strategy.
+    /// Construct a canvas from an explicit strategy, placed at the top-left corner of the
+    /// target's bounding box.
     pub fn with_strategy(target: &'a mut T, strategy: S) -> Self {
-        Self { strategy, target }
+        let origin = target.bounding_box().top_left;
+        Self::with_strategy_at(target, strategy, origin)
+    }
+
+    /// Construct a canvas covering part of the target, with its top-left corner at `origin`.
+    /// Drawing uses coordinates local to the canvas; flush addresses only its window.
+    pub fn with_strategy_at(target: &'a mut T, strategy: S, origin: Point) -> Self {
+        Self::from_parts(target, strategy, origin)
+    }
+}
+
+impl<T, S> OwnedCanvas<T, S>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+{
+    /// Construct a canvas that takes ownership of `target`, placed at the top-left corner of its
+    /// bounding box, e.g. a [`Region`](crate::Region) of a shared display.
+    pub fn owned(target: T, strategy: S) -> Self {
+        let origin = target.bounding_box().top_left;
+        Self::owned_at(target, strategy, origin)
+    }
+
+    /// Like [`owned`](Self::owned), with the canvas's top-left corner at `origin` on the target.
+    pub fn owned_at(target: T, strategy: S, origin: Point) -> Self {
+        Self::from_parts(target, strategy, origin)
+    }
+}
+
+impl<'a, T, S, H> Canvas<'a, T, S, H>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
+{
+    fn from_parts(target: H, strategy: S, origin: Point) -> Self {
+        Self {
+            strategy,
+            target,
+            _lifetime: PhantomData,
+            _target: PhantomData,
+            origin,
+            orientation: Orientation::default(),
+            scale: 1,
+            stats: FrameStats::default(),
+            stale: true,
+            stepping: false,
+        }
+    }
+
+    /// Give back the target and the strategy.
+    pub fn into_parts(self) -> (H, S) {
+        (self.target, self.strategy)
+    }
+
+    #[inline]
+    pub fn target(&self) -> &T {
+        self.target.borrow()
+    }
+
+    /// The target, e.g. to send commands between flushes. Drawing on it directly bypasses the
+    /// canvas and gets overwritten by the next flush of the same area.
+    #[inline]
+    pub fn target_mut(&mut self) -> &mut T {
+        self.target.borrow_mut()
+    }
+
+    #[inline]
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// The strategy, e.g. to reach [`TripleBuffer::present`] or a [`DiffPolicy`]. The next flush
+    /// always runs, since drawing through it is not counted.
+    #[inline]
+    pub fn strategy_mut(&mut self) -> &mut S {
+        self.stale = true;
+        &mut self.strategy
+    }
+
+    /// Area of the target covered by this canvas.
+    pub fn window(&self) -> Rectangle {
+        let size = self.strategy.visible().size;
+        Rectangle::new(self.origin, self.orientation.apply_size(size) * self.scale)
+    }
+
+    /// Lay the image out on the panel in `orientation`. The strategy must be sized for the image
+    /// as drawn, e.g. 320x240 for a 240x320 panel rotated by 90 degrees.
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    #[inline]
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    #[inline]
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+    }
+
+    /// Present every drawn pixel as a `scale` x `scale` block, e.g. a 120x160 strategy on a
+    /// 240x320 panel with a scale of 2. Drawing, blending and dirty tracking stay at low resolution.
+    ///
+    /// Panics if `scale` is 0.
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        self.set_scale(scale);
+        self
+    }
+
+    #[inline]
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Panics if `scale` is 0.
+    #[inline]
+    pub fn set_scale(&mut self, scale: u32) {
+        assert!(scale > 0, "scale must be at least 1");
+        self.scale = scale;
+    }
+
+    /// Start a frame that is flushed when it ends or is dropped.
+    pub fn begin_frame(&mut self) -> Frame<'_, 'a, T, S, H> {
+        Frame::new(self)
+    }
+
+    /// Draw one frame with `draw`, then flush it.
+    pub fn frame<F>(&mut self, draw: F) -> Result<FrameStats, T::Error>
+    where
+        F: FnOnce(&mut Frame<'_, 'a, T, S, H>),
+    {
+        let mut frame = self.begin_frame();
+        draw(&mut frame);
+        frame.end()
+    }
+
+    /// Counters for the frame drawn since the previous flush.
+    #[inline]
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Send the frame and report what it cost. When nothing was drawn since the previous flush
+    /// the target is not touched at all. After an error the counters are kept and the next flush
+    /// sends what is left.
+    pub fn flush(&mut self) -> Result<FrameStats, T::Error> {
+        if self.stats.is_unchanged() && !self.stale && !self.stepping {
+            return Ok(core::mem::take(&mut self.stats));
+        }
+        let frame = self.strategy.bounding_box();
+        let mut counted = Counted::new(self.target.borrow_mut());
+        let mut out = Output::new(&mut counted, frame)
+            .with_origin(self.origin)
+            .with_orientation(self.orientation)
+            .with_scale(self.scale);
+        if let Err(e) = self.strategy.flush_to(&mut out) {
+            self.stale = true;
+            return Err(e);
+        }
+        self.stale = false;
+        self.stepping = false;
+        let mut stats = core::mem::take(&mut self.stats);
+        counted.finish(&mut stats);
+        Ok(stats)
     }
 
-    pub fn flush(&mut self) -> Result<(), T::Error> {
-        self.strategy.flush(self.target)
+    /// Send up to `max_pixels` of the current frame, in whole rows, resuming where the previous
+    /// call stopped. Returns `true` once the frame is complete; drawing between steps lands in the
+    /// next frame.
+    pub fn flush_step(&mut self, max_pixels: u32) -> Result<bool, T::Error>
+    where
+        S: StepFlush,
+    {
+        if !self.stepping {
+            // Everything drawn so far goes out with this frame; the counters start on the next.
+            self.stats = FrameStats::default();
+            self.stale = false;
+            self.stepping = true;
+        }
+        let frame = self.strategy.bounding_box();
+        let mut out = Output::new(self.target.borrow_mut(), frame)
+            .with_origin(self.origin)
+            .with_orientation(self.orientation)
+            .with_scale(self.scale);
+        let done = self.strategy.flush_step_to(&mut out, max_pixels)?;
+        self.stepping = !done;
+        Ok(done)
     }
 }
 
-impl<'a, T, S> OriginDimensions for Canvas<'a, T, S>
+impl<'a, T, S, H> OriginDimensions for Canvas<'a, T, S, H>
 where
-    T: DrawTarget + OriginDimensions,
+    T: DrawTarget,
     S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
 {
     fn size(&self) -> Size {
-        self.target.size()
+        self.strategy.bounding_box().size
     }
 }
 
@@ -230,6 +1356,41 @@ where
         let size = target.bounding_box().size;
         Self::with_strategy(target, DoubleBuffer::new(size.width, size.height))
     }
+
+    /// Double buffer only `window` of the target; `N` must match its size.
+    pub fn double_buffered_window(target: &'a mut T, window: Rectangle) -> Self {
+        let size = window.size;
+        let strategy = DoubleBuffer::new(size.width, size.height);
+        Self::with_strategy_at(target, strategy, window.top_left)
+    }
+}
+
+impl<'a, T, C, const N: usize, B, H> Canvas<'a, T, DoubleBuffer<C, N, B>, H>
+where
+    C: PixelColor,
+    T: DrawTarget<Color = C>,
+    B: PixelStorage<C>,
+    H: BorrowMut<T>,
+{
+    /// Send one step of `transition` from the previously flushed frame to the newly drawn one;
+    /// see [`DoubleBuffer::transition_to`].
+    pub fn transition(&mut self, transition: Transition, progress: u8) -> Result<(), T::Error>
+    where
+        Rgba<C>: Blend<C>,
+    {
+        let frame = self.strategy.bounding_box();
+        let mut out = Output::new(self.target.borrow_mut(), frame)
+            .with_origin(self.origin)
+            .with_orientation(self.orientation)
+            .with_scale(self.scale);
+        self.strategy
+            .transition_to(&mut out, transition, progress)?;
+        if progress == u8::MAX {
+            self.stats = FrameStats::default();
+            self.stale = false;
+        }
+        Ok(())
+    }
 }
 
 impl<'a, T, C, const N: usize> Canvas<'a, T, SingleBuffer<C, N>>
@@ -241,27 +1402,114 @@ where
         let size = target.bounding_box().size;
         Self::with_strategy(target, SingleBuffer::new(size.width, size.height))
     }
+
+    /// Buffer only `window` of the target; `N` must match its size.
+    pub fn single_buffered_window(target: &'a mut T, window: Rectangle) -> Self {
+        let size = window.size;
+        let strategy = SingleBuffer::new(size.width, size.height);
+        Self::with_strategy_at(target, strategy, window.top_left)
+    }
 }
 
-impl<'a, T, S> Canvas<'a, T, S>
+impl<'a, T, C, const N: usize> Canvas<'a, T, TripleBuffer<C, N>>
+where
+    C: RgbColor,
+    T: DrawTarget<Color = C>,
+{
+    pub fn triple_buffered(target: &'a mut T) -> Self {
+        let size = target.bounding_box().size;
+        Self::with_strategy(target, TripleBuffer::new(size.width, size.height))
+    }
+}
+
+impl<'a, T, C, const N: usize> Canvas<'a, T, VirtualBuffer<C, N>>
+where
+    C: RgbColor,
+    T: DrawTarget<Color = C>,
+{
+    /// A `width` x `height` surface viewed through the target; `N` must be `width * height`.
+    pub fn virtual_buffered(target: &'a mut T, width: u32, height: u32) -> Self {
+        let view = target.bounding_box().size;
+        Self::with_strategy(target, VirtualBuffer::new(width, height, view))
+    }
+}
+
+impl<'a, T, C, const N: usize, B, H> Canvas<'a, T, VirtualBuffer<C, N, B>, H>
+where
+    C: RgbColor,
+    T: DrawTarget<Color = C>,
+    B: Storage<C>,
+    H: BorrowMut<T>,
+{
+    /// Visible part of the surface.
+    pub fn viewport(&self) -> Rectangle {
+        self.strategy.viewport()
+    }
+
+    /// Scroll the surface so `top_left` lands in the display's corner; sent on the next flush.
+    pub fn set_viewport(&mut self, top_left: Point) {
+        self.strategy.set_viewport(top_left);
+        self.stale = true;
+    }
+}
+
+impl<'a, T, C, const N: usize, const H: usize> Canvas<'a, T, HashBuffer<C, N, H>>
+where
+    C: RgbColor,
+    T: DrawTarget<Color = C>,
+{
+    pub fn hash_buffered(target: &'a mut T) -> Self {
+        let size = target.bounding_box().size;
+        Self::with_strategy(target, HashBuffer::new(size.width, size.height))
+    }
+}
+
+impl<'a, T, C, const N: usize> Canvas<'a, T, LineBuffer<C, N>>
+where
+    C: RgbColor,
+    T: DrawTarget<Color = C>,
+{
+    pub fn line_buffered(target: &'a mut T) -> Self {
+        let size = target.bounding_box().size;
+        Self::with_strategy(target, LineBuffer::new(size.width, size.height))
+    }
+
+    /// Render a full frame: `draw` is replayed once per band, clipped to it, and every band is
+    /// flushed right after. The band keeps the previous band's pixels, so `draw` should paint a
+    /// background first.
+    pub fn render<F>(&mut self, mut draw: F) -> Result<(), T::Error>
+    where
+        F: FnMut(&mut Self),
+    {
+        for band in 0..self.strategy.bands() {
+            self.strategy.select_band(band);
+            draw(self);
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, S, H> Canvas<'a, T, S, H>
 where
     T: DrawTarget,
     S: BufferStrategy<Color = T::Color>,
-    T::Color: RgbColor,
+    H: BorrowMut<T>,
     Rgba<S::Color>: Blend<S::Color>,
 {
-    pub fn alpha<const N: usize>(&mut self) -> AlphaCanvas<'_, S::Color, N>
+    pub fn alpha<const N: usize>(&mut self) -> AlphaCanvas<'_, S::Color, N, S::Storage>
     where
         S: HasFramebuffer<S::Color, N>,
     {
-        AlphaCanvas::new(self.strategy.current_mut())
+        AlphaCanvas::counted(self.strategy.current_mut(), &mut self.stats.pixels_blended)
     }
 }
 
-impl<'a, T, S> DrawTarget for Canvas<'a, T, S>
+impl<'a, T, S, H> DrawTarget for Canvas<'a, T, S, H>
 where
-    T: DrawTarget + OriginDimensions,
+    T: DrawTarget,
     S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
 {
     type Error = S::Error;
     type Color = S::Color;
@@ -271,6 +1519,13 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
+        let bounds = self.strategy.bounding_box();
+        let written = &mut self.stats.pixels_written;
+        let pixels = pixels.into_iter().inspect(|Pixel(p, _)| {
+            if bounds.contains(*p) {
+                *written += 1;
+            }
+        });
         self.strategy.draw_iter(pixels)
     }
 
@@ -279,16 +1534,446 @@ where
     where
         I: IntoIterator<Item = Self::Color>,
     {
+        self.stats.pixels_written += clipped_pixels(area, &self.strategy.bounding_box());
         self.strategy.fill_contiguous(area, colors)
     }
 
     #[inline(always)]
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), S::Error> {
+        self.stats.pixels_written += clipped_pixels(area, &self.strategy.bounding_box());
         self.strategy.fill_solid(area, color)
     }
 
     #[inline(always)]
     fn clear(&mut self, color: Self::Color) -> Result<(), S::Error> {
+        let size = self.strategy.bounding_box().size;
+        self.stats.pixels_written += size.width * size.height;
         self.strategy.clear(color)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::MockTransfer;
+    use core::mem::MaybeUninit;
+    use embedded_graphics_core::pixelcolor::Rgb565;
+    use std::boxed::Box;
+
+    /// 4x5 triple buffer over leaked memory, standing in for `static`s.
+    fn triple() -> TripleBuffer<Rgb565, 20, &'static mut [Rgb565; 20]> {
+        let leak = || Framebuffer::init(Box::leak(Box::new(MaybeUninit::uninit())), 4, 5);
+        TripleBuffer::from_framebuffers([leak(), leak(), leak()])
+    }
+
+    /// Render `draw` through a line buffer holding `N` pixels onto a 4x5 framebuffer.
+    fn render_lines<const N: usize>(
+        draw: impl Fn(&mut Canvas<'_, Framebuffer<Rgb565, 20>, LineBuffer<Rgb565, N>>),
+    ) -> Framebuffer<Rgb565, 20> {
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        let mut canvas = Canvas::<_, LineBuffer<Rgb565, N>>::line_buffered(&mut display);
+        canvas.render(|c| draw(c)).unwrap();
+        display
+    }
+
+    fn frame(canvas: &mut impl DrawTarget<Color = Rgb565, Error = Infallible>) {
+        canvas.clear(Rgb565::BLUE).unwrap();
+        canvas
+            .fill_solid(
+                &Rectangle::new(Point::new(1, 1), Size::new(2, 3)),
+                Rgb565::RED,
+            )
+            .unwrap();
+    }
+
+    fn expected(p: Point) -> Rgb565 {
+        if (1..3).contains(&p.x) && (1..4).contains(&p.y) {
+            Rgb565::RED
+        } else {
+            Rgb565::BLUE
+        }
+    }
+
+    #[test]
+    fn line_buffer_uses_whole_rows_of_n() {
+        // 10 pixels hold two rows of 4 with 2 spare.
+        let lines = LineBuffer::<Rgb565, 10>::new(4, 5);
+        assert_eq!((lines.rows(), lines.bands()), (2, 3));
+
+        let display = render_lines::<10>(|c| frame(c));
+        for p in display.bounding_box().points() {
+            assert_eq!(display.get_pixel(p), Some(expected(p)), "at {p:?}");
+        }
+    }
+
+    #[test]
+    fn line_buffer_band_rows() {
+        let mut lines = LineBuffer::<Rgb565, 10>::new(4, 5);
+        assert!(lines.current_mut().rows_mut().all(|row| row.len() == 4));
+        assert_eq!(lines.current_mut().rows_mut().count(), 2);
+    }
+
+    #[test]
+    fn line_buffer_larger_than_frame() {
+        let lines = LineBuffer::<Rgb565, 64>::new(4, 5);
+        assert_eq!((lines.rows(), lines.bands()), (5, 1));
+
+        let display = render_lines::<64>(|c| frame(c));
+        for p in display.bounding_box().points() {
+            assert_eq!(display.get_pixel(p), Some(expected(p)), "at {p:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "N must hold at least one row")]
+    fn line_buffer_rejects_n_below_one_row() {
+        LineBuffer::<Rgb565, 3>::new(4, 5);
+    }
+
+    /// 4x5 display that accepts `skip` draw calls, then refuses the next `fails`.
+    struct Flaky {
+        fb: Framebuffer<Rgb565, 20>,
+        skip: u32,
+        fails: u32,
+    }
+
+    impl Flaky {
+        fn refuse(&mut self) -> Result<(), ()> {
+            if self.skip > 0 {
+                self.skip -= 1;
+                Ok(())
+            } else if self.fails > 0 {
+                self.fails -= 1;
+                Err(())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl OriginDimensions for Flaky {
+        fn size(&self) -> Size {
+            self.fb.size()
+        }
+    }
+
+    impl DrawTarget for Flaky {
+        type Color = Rgb565;
+        type Error = ();
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), ()>
+        where
+            I: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            self.refuse()?;
+            self.fb.draw_iter(pixels).unwrap();
+            Ok(())
+        }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), ()>
+        where
+            I: IntoIterator<Item = Rgb565>,
+        {
+            self.refuse()?;
+            self.fb.fill_contiguous(area, colors).unwrap();
+            Ok(())
+        }
+    }
+
+    /// Fail the second send of a two-area flush, then check the retry completes the frame.
+    fn retry_after_failure<S>(mut strategy: S)
+    where
+        S: BufferStrategy<Color = Rgb565, Error = Infallible>,
+    {
+        let mut display = Flaky {
+            fb: Framebuffer::new(4, 5),
+            skip: 0,
+            fails: 0,
+        };
+        strategy.clear(Rgb565::BLUE).unwrap();
+        strategy.flush(&mut display).unwrap();
+
+        let corners = [Point::new(0, 0), Point::new(3, 4)];
+        for p in corners {
+            Pixel(p, Rgb565::RED).draw(&mut strategy).unwrap();
+        }
+        (display.skip, display.fails) = (1, 1);
+        assert_eq!(strategy.flush(&mut display), Err(()));
+        strategy.flush(&mut display).unwrap();
+
+        for p in display.fb.bounding_box().points() {
+            let color = if corners.contains(&p) {
+                Rgb565::RED
+            } else {
+                Rgb565::BLUE
+            };
+            assert_eq!(display.fb.get_pixel(p), Some(color), "at {p:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "H must be height")]
+    fn hash_buffer_rejects_wrong_height() {
+        HashBuffer::<Rgb565, 20, 4>::new(4, 5);
+    }
+
+    #[test]
+    fn canvases_over_side_by_side_regions() {
+        let shared = SharedDisplay::<_, 2>::new(Framebuffer::<Rgb565, 20>::new(4, 5));
+        let mut left = shared
+            .claim(Rectangle::new(Point::zero(), Size::new(2, 5)))
+            .unwrap();
+        let mut right = shared
+            .claim(Rectangle::new(Point::new(2, 0), Size::new(2, 5)))
+            .unwrap();
+
+        let mut canvas = Canvas::<_, SingleBuffer<Rgb565, 10>>::single_buffered(&mut left);
+        canvas.clear(Rgb565::RED).unwrap();
+        canvas.flush().unwrap();
+        let mut canvas = Canvas::<_, DoubleBuffer<Rgb565, 10>>::double_buffered(&mut right);
+        canvas.clear(Rgb565::GREEN).unwrap();
+        Pixel(Point::zero(), Rgb565::BLUE)
+            .draw(&mut canvas)
+            .unwrap();
+        canvas.flush().unwrap();
+
+        drop((left, right));
+        let display = shared.into_inner();
+        for p in display.bounding_box().points() {
+            let color = match p {
+                Point { x: 2, y: 0 } => Rgb565::BLUE,
+                Point { x: 0..2, .. } => Rgb565::RED,
+                _ => Rgb565::GREEN,
+            };
+            assert_eq!(display.get_pixel(p), Some(color), "at {p:?}");
+        }
+    }
+
+    #[test]
+    fn virtual_canvas_window_is_the_viewport() {
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        let canvas = Canvas::<_, VirtualBuffer<Rgb565, 40>>::virtual_buffered(&mut display, 8, 5);
+        assert_eq!(canvas.size(), Size::new(8, 5));
+        assert_eq!(
+            canvas.window(),
+            Rectangle::new(Point::zero(), Size::new(4, 5))
+        );
+    }
+
+    #[test]
+    fn owned_canvas_over_offset_region() {
+        let shared = SharedDisplay::<_, 1>::new(Framebuffer::<Rgb565, 20>::new(4, 5));
+        let region = shared
+            .claim(Rectangle::new(Point::new(2, 0), Size::new(2, 5)))
+            .unwrap();
+        let mut canvas = OwnedCanvas::owned(region, SingleBuffer::<Rgb565, 10>::new(2, 5));
+        canvas.clear(Rgb565::RED).unwrap();
+        canvas.flush().unwrap();
+
+        drop(canvas);
+        let display = shared.into_inner();
+        for p in display.bounding_box().points() {
+            let color = if p.x >= 2 { Rgb565::RED } else { Rgb565::BLACK };
+            assert_eq!(display.get_pixel(p), Some(color), "at {p:?}");
+        }
+    }
+
+    #[test]
+    fn canvas_keeps_stats_after_failed_flush() {
+        let mut display = Flaky {
+            fb: Framebuffer::new(4, 5),
+            skip: 0,
+            fails: 1,
+        };
+        let mut canvas = Canvas::<_, SingleBuffer<Rgb565, 20>>::single_buffered(&mut display);
+        frame(&mut canvas);
+        let drawn = canvas.stats();
+        assert_eq!(drawn.pixels_written, 26);
+
+        assert_eq!(canvas.flush(), Err(()));
+        assert_eq!(canvas.stats(), drawn);
+        let stats = canvas.flush().unwrap();
+        assert_eq!(stats.pixels_written, 26);
+        assert_eq!(stats.rects_flushed, 1);
+        assert!(canvas.stats().is_unchanged());
+
+        for p in display.fb.bounding_box().points() {
+            assert_eq!(display.fb.get_pixel(p), Some(expected(p)), "at {p:?}");
+        }
+    }
+
+    #[test]
+    fn drawing_between_steps_reaches_the_next_flush() {
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        let mut canvas = Canvas::<_, DoubleBuffer<Rgb565, 20>>::double_buffered(&mut display);
+        frame(&mut canvas);
+        assert!(!canvas.flush_step(4).unwrap());
+        Pixel(Point::zero(), Rgb565::GREEN)
+            .draw(&mut canvas)
+            .unwrap();
+        while !canvas.flush_step(4).unwrap() {}
+
+        let stats = canvas.flush().unwrap();
+        assert_eq!(stats.pixels_written, 1);
+        assert_eq!(stats.rects_flushed, 1);
+        assert_eq!(display.get_pixel(Point::zero()), Some(Rgb565::GREEN));
+        for p in display.bounding_box().points().skip(1) {
+            assert_eq!(display.get_pixel(p), Some(expected(p)), "at {p:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "flush_to while a transfer is in flight")]
+    fn triple_buffer_flush_to_refuses_running_transfer() {
+        // Not dropped, so the assert under test is the only panic.
+        let mut triple = core::mem::ManuallyDrop::new(triple());
+        let mut transfer = MockTransfer::<Rgb565, 20>::new(Rgb565::BLACK, 1);
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        triple.present();
+        assert!(triple.poll_flush(&mut transfer).is_pending());
+        let _ = triple.flush(&mut display);
+    }
+
+    #[test]
+    fn triple_buffer_flush_to_after_transfer() {
+        let mut triple = triple();
+        let mut transfer = MockTransfer::<Rgb565, 20>::new(Rgb565::BLACK, 1);
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        frame(&mut triple);
+        triple.present();
+        while triple.poll_flush(&mut transfer).is_pending() {}
+        let sent = Rectangle::new(Point::zero(), Size::new(4, 5));
+        assert_eq!(transfer.area(), sent);
+        assert!(
+            transfer
+                .pixels()
+                .iter()
+                .copied()
+                .eq(sent.points().map(expected))
+        );
+
+        // Once the transfer is done, a blocking flush sends only what changed since.
+        Pixel(Point::new(0, 4), Rgb565::GREEN)
+            .draw(&mut triple)
+            .unwrap();
+        triple.flush(&mut display).unwrap();
+        for p in display.bounding_box().points() {
+            let color = if p == Point::new(0, 4) {
+                Rgb565::GREEN
+            } else {
+                Rgb565::BLACK
+            };
+            assert_eq!(display.get_pixel(p), Some(color), "at {p:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "dropped while a transfer is in flight")]
+    fn triple_buffer_drop_refuses_running_transfer() {
+        let mut triple = triple();
+        let mut transfer = MockTransfer::<Rgb565, 20>::new(Rgb565::BLACK, 1);
+        triple.clear(Rgb565::BLUE).unwrap();
+        triple.present();
+        assert!(triple.poll_flush(&mut transfer).is_pending());
+        drop(triple);
+    }
+
+    /// Transport without stride support, holding a single 4-pixel row.
+    struct RowsOnly(MockTransfer<Rgb565, 4>);
+
+    impl Transfer<Rgb565> for RowsOnly {
+        type Error = ();
+
+        unsafe fn start(&mut self, area: &Rectangle, pixels: &[Rgb565]) -> Result<(), ()> {
+            // SAFETY: forwarded; the mock copies the pixels right away.
+            unsafe { self.0.start(area, pixels) }
+        }
+
+        fn poll(&mut self) -> Poll<Result<(), ()>> {
+            self.0.poll()
+        }
+    }
+
+    #[test]
+    fn triple_buffer_starts_padded_rows_between_polls() {
+        let leak = || {
+            Framebuffer::from_storage_with_stride(Box::leak(Box::new([Rgb565::BLACK; 25])), 4, 5, 5)
+        };
+        let mut triple =
+            TripleBuffer::<Rgb565, 25, &'static mut [Rgb565; 25]>::from_framebuffers([
+                leak(),
+                leak(),
+                leak(),
+            ]);
+        let mut transfer = RowsOnly(MockTransfer::new(Rgb565::BLACK, 1));
+
+        triple.clear(Rgb565::BLUE).unwrap();
+        triple.present();
+        assert!(triple.poll_flush(&mut transfer).is_pending());
+        assert_eq!(transfer.0.started(), 1);
+
+        // A frame presented meanwhile waits for every row of the one being sent.
+        triple.clear(Rgb565::RED).unwrap();
+        triple.present();
+        while triple.poll_flush(&mut transfer).is_pending() {
+            if transfer.0.started() <= 5 {
+                assert_eq!(transfer.0.pixels(), &[Rgb565::BLUE; 4]);
+            }
+        }
+        assert_eq!(transfer.0.started(), 10);
+        assert_eq!(
+            transfer.0.area(),
+            Rectangle::new(Point::new(0, 4), Size::new(4, 1))
+        );
+        assert_eq!(transfer.0.pixels(), &[Rgb565::RED; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "scale must be at least 1")]
+    fn zero_scale_is_refused() {
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        let _ = Canvas::<_, SingleBuffer<Rgb565, 20>>::single_buffered(&mut display).with_scale(0);
+    }
+
+    #[test]
+    fn double_buffer_steps_keep_reference_in_step() {
+        let mut display = Framebuffer::<Rgb565, 20>::new(4, 5);
+        let mut canvas = Canvas::<_, DoubleBuffer<Rgb565, 20>>::double_buffered(&mut display);
+        canvas.clear(Rgb565::BLUE).unwrap();
+        canvas.flush().unwrap();
+
+        canvas.clear(Rgb565::RED).unwrap();
+        assert!(!canvas.flush_step(4).unwrap());
+        // Row 0 is on the panel already, row 3 is not.
+        let (sent, unsent) = (Point::new(1, 0), Point::new(1, 3));
+        Pixel(sent, Rgb565::GREEN).draw(&mut canvas).unwrap();
+        Pixel(unsent, Rgb565::GREEN).draw(&mut canvas).unwrap();
+        while !canvas.flush_step(4).unwrap() {}
+        assert_eq!(canvas.target().get_pixel(sent), Some(Rgb565::RED));
+        assert_eq!(canvas.target().get_pixel(unsent), Some(Rgb565::GREEN));
+
+        // Only the pixel changed after its row went out differs from the reference now.
+        let stats = canvas.flush().unwrap();
+        assert_eq!((stats.rects_flushed, stats.bytes_sent), (1, 2));
+        for p in display.bounding_box().points() {
+            let color = if p == sent || p == unsent {
+                Rgb565::GREEN
+            } else {
+                Rgb565::RED
+            };
+            assert_eq!(display.get_pixel(p), Some(color), "at {p:?}");
+        }
+    }
+
+    #[test]
+    fn failed_flush_is_retried() {
+        retry_after_failure(SingleBuffer::<Rgb565, 20>::new(4, 5));
+        retry_after_failure(DoubleBuffer::<Rgb565, 20>::new(4, 5));
+        retry_after_failure(TripleBuffer::<Rgb565, 20>::new(4, 5));
+        retry_after_failure(VirtualBuffer::<Rgb565, 20>::new(4, 5, Size::new(4, 5)));
+        retry_after_failure(HashBuffer::<Rgb565, 20, 5>::new(4, 5));
+        retry_after_failure(PingPongBuffer::<Rgb565, 20>::new(4, 5));
+    }
+}
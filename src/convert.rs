@@ -0,0 +1,206 @@
+use core::marker::PhantomData;
+use embedded_graphics_core::pixelcolor::*;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+/// 4x4 ordered dithering thresholds in sixteenths.
+const BAYER4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Lets a target of a coarser color type receive `C` pixels, quantizing them on the way out.
+///
+/// Put it in front of the display to render into a high-precision framebuffer, e.g. `Rgb888`, so
+/// blending happens at full depth, and flush to an `Rgb565` or `Rgb666` panel. With dithering
+/// on, a 4x4 Bayer pattern in panel coordinates hides the banding of smooth gradients.
+pub struct Quantize<T, C> {
+    target: T,
+    dither: bool,
+    _color: PhantomData<C>,
+}
+
+impl<T, C> Quantize<T, C>
+where
+    T: DrawTarget,
+    T::Color: RgbColor + From<Rgb888>,
+    C: RgbColor,
+{
+    pub fn new(target: T) -> Self {
+        Self {
+            target,
+            dither: false,
+            _color: PhantomData,
+        }
+    }
+
+    /// Enable ordered dithering.
+    pub fn dithered(mut self) -> Self {
+        self.dither = true;
+        self
+    }
+
+    #[inline]
+    pub fn set_dither(&mut self, dither: bool) {
+        self.dither = dither;
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.target
+    }
+
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> T {
+        self.target
+    }
+}
+
+/// Quantize `color` shown at panel point `p`.
+#[inline(always)]
+fn convert<C, D>(color: C, p: Point, dither: bool) -> D
+where
+    C: RgbColor,
+    D: RgbColor + From<Rgb888>,
+{
+    // Rounding is the same as a threshold of one half.
+    let t = if dither {
+        BAYER4[(p.y & 3) as usize][(p.x & 3) as usize] as u32
+    } else {
+        8
+    };
+    let r = channel(color.r(), C::MAX_R, D::MAX_R, t);
+    let g = channel(color.g(), C::MAX_G, D::MAX_G, t);
+    let b = channel(color.b(), C::MAX_B, D::MAX_B, t);
+    Rgb888::new(r, g, b).into()
+}
+
+/// Requantize `v` from `0..=from` to `0..=to` using threshold `t` sixteenths, then widen the result
+/// to 8 bits so the `Rgb888` conversion lands exactly on it.
+#[inline(always)]
+fn channel(v: u8, from: u8, to: u8, t: u32) -> u8 {
+    let (v, from, to) = (v as u32, from as u32, to as u32);
+    let q = ((v * to * 16 + t * from) / (from * 16)).min(to);
+    ((q * 255 + to / 2) / to) as u8
+}
+
+impl<T, C> Dimensions for Quantize<T, C>
+where
+    T: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<T, C> DrawTarget for Quantize<T, C>
+where
+    T: DrawTarget,
+    T::Color: RgbColor + From<Rgb888>,
+    C: RgbColor,
+{
+    type Color = C;
+    type Error = T::Error;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let dither = self.dither;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(p, c)| Pixel(p, convert(c, p, dither))),
+        )
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let dither = self.dither;
+        let colors = area
+            .points()
+            .zip(colors)
+            .map(|(p, c)| convert(c, p, dither));
+        self.target.fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.dither {
+            let colors = area.points().map(|p| convert(color, p, true));
+            return self.target.fill_contiguous(area, colors);
+        }
+        let color = convert(color, Point::zero(), false);
+        self.target.fill_solid(area, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Framebuffer;
+
+    /// 5- and 6-bit channels of `v` rounded to the nearest level.
+    fn nearest(v: u8) -> (u8, u8) {
+        let v = v as u32;
+        (((v * 31 + 127) / 255) as u8, ((v * 63 + 127) / 255) as u8)
+    }
+
+    #[test]
+    fn rounding_lands_on_nearest_level() {
+        for v in 0..=255 {
+            let c: Rgb565 = convert(Rgb888::new(v, v, v), Point::zero(), false);
+            let (r, g) = nearest(v);
+            assert_eq!((c.r(), c.g(), c.b()), (r, g, r), "from {v}");
+        }
+    }
+
+    #[test]
+    fn same_depth_is_unchanged_at_every_threshold() {
+        for v in 0..=63 {
+            for p in Rectangle::new(Point::zero(), Size::new(4, 4)).points() {
+                let c = Rgb565::new(v & 0x1F, v, v & 0x1F);
+                assert_eq!(convert::<_, Rgb565>(c, p, true), c, "{v} at {p:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn dithering_averages_to_the_exact_level() {
+        // Over the 16 thresholds of a 4x4 tile the levels sum to 16x the exact value, rounded down.
+        for v in 0..=255 {
+            let tile = Rectangle::new(Point::zero(), Size::new(4, 4));
+            let (mut r, mut g) = (0, 0);
+            for p in tile.points() {
+                let c: Rgb565 = convert(Rgb888::new(v, v, v), p, true);
+                r += c.r() as u32;
+                g += c.g() as u32;
+            }
+            let v = v as u32;
+            assert_eq!((r, g), (v * 31 * 16 / 255, v * 63 * 16 / 255), "from {v}");
+        }
+    }
+
+    #[test]
+    fn dithered_extremes_stay_solid() {
+        let mut quantize =
+            Quantize::<_, Rgb888>::new(Framebuffer::<Rgb565, 64>::new(8, 8)).dithered();
+        let area = Rectangle::new(Point::zero(), Size::new(8, 4));
+        quantize.fill_solid(&area, Rgb888::WHITE).unwrap();
+        quantize
+            .fill_solid(&Rectangle::new(Point::new(0, 4), area.size), Rgb888::BLACK)
+            .unwrap();
+        let fb = quantize.into_inner();
+        for p in fb.bounding_box().points() {
+            let expected = if p.y < 4 {
+                Rgb565::WHITE
+            } else {
+                Rgb565::BLACK
+            };
+            assert_eq!(fb.get_pixel(p), Some(expected), "at {p:?}");
+        }
+    }
+}
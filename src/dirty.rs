@@ -0,0 +1,299 @@
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+/// Maximum number of disjoint rectangles tracked before they get merged together.
+pub const DIRTY_REGIONS: usize = 8;
+
+/// Bounded set of rectangles touched since the last flush.
+///
+/// Overlapping or adjacent areas are merged on insert; once the set is full a new area is merged
+/// into the rectangle whose bounding box grows the least.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DirtyRegions {
+    rects: [Rectangle; DIRTY_REGIONS],
+    len: usize,
+}
+
+impl DirtyRegions {
+    pub const fn new() -> Self {
+        Self {
+            rects: [Rectangle::zero(); DIRTY_REGIONS],
+            len: 0,
+        }
+    }
+
+    /// A set holding just `area`.
+    pub(crate) const fn with_area(area: Rectangle) -> Self {
+        let mut regions = Self::new();
+        if !area.is_zero_sized() {
+            regions.rects[0] = area;
+            regions.len = 1;
+        }
+        regions
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Rectangle> + '_ {
+        self.rects[..self.len].iter().copied()
+    }
+
+    /// Record `area` as dirty.
+    pub fn add(&mut self, area: Rectangle) {
+        if area.is_zero_sized() {
+            return;
+        }
+
+        // Absorb every rectangle the new area touches; the union may then touch others.
+        let mut area = area;
+        let mut i = 0;
+        while i < self.len {
+            if touches(&self.rects[i], &area) {
+                area = union(&self.rects[i], &area);
+                self.len -= 1;
+                self.rects[i] = self.rects[self.len];
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+
+        if self.len < DIRTY_REGIONS {
+            self.rects[self.len] = area;
+            self.len += 1;
+            return;
+        }
+
+        let (best, _) = self
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (i, self::area(&union(&r, &area)) - self::area(&r)))
+            .min_by_key(|&(_, growth)| growth)
+            .unwrap();
+        let merged = union(&self.rects[best], &area);
+        self.len -= 1;
+        self.rects[best] = self.rects[self.len];
+        self.add(merged);
+    }
+}
+
+impl Default for DirtyRegions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Smallest rectangle covering both `a` and `b`.
+#[inline]
+pub(crate) fn union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    if a.is_zero_sized() {
+        return *b;
+    }
+    if b.is_zero_sized() {
+        return *a;
+    }
+    let tl = a.top_left.component_min(b.top_left);
+    let br = (a.top_left + a.size).component_max(b.top_left + b.size);
+    Rectangle::with_corners(tl, br - Point::new(1, 1))
+}
+
+/// Whether `a` and `b` overlap or share an edge.
+#[inline]
+fn touches(a: &Rectangle, b: &Rectangle) -> bool {
+    let (a0, a1) = (a.top_left, a.top_left + a.size);
+    let (b0, b1) = (b.top_left, b.top_left + b.size);
+    a0.x <= b1.x && b0.x <= a1.x && a0.y <= b1.y && b0.y <= a1.y
+}
+
+#[inline]
+fn area(r: &Rectangle) -> u64 {
+    r.size.width as u64 * r.size.height as u64
+}
+
+/// Running bounding box of individually drawn pixels.
+pub(crate) struct Touched {
+    min: Point,
+    max: Point,
+}
+
+impl Touched {
+    #[inline(always)]
+    pub(crate) fn new() -> Self {
+        Self {
+            min: Point::new(i32::MAX, i32::MAX),
+            max: Point::new(i32::MIN, i32::MIN),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn add(&mut self, p: Point) {
+        self.min = self.min.component_min(p);
+        self.max = self.max.component_max(p);
+    }
+
+    #[inline]
+    pub(crate) fn area(&self) -> Rectangle {
+        if self.min.x > self.max.x {
+            Rectangle::zero()
+        } else {
+            Rectangle::with_corners(self.min, self.max)
+        }
+    }
+}
+
+/// Position within a frame that is being flushed in steps.
+#[derive(Clone, Copy)]
+pub(crate) struct FlushCursor {
+    pending: DirtyRegions,
+    rect: usize,
+    row: u32,
+}
+
+impl FlushCursor {
+    pub(crate) const fn new() -> Self {
+        Self {
+            pending: DirtyRegions::new(),
+            rect: 0,
+            row: 0,
+        }
+    }
+
+    /// Whether no frame is in progress.
+    #[inline]
+    pub(crate) fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub(crate) fn start(&mut self, regions: DirtyRegions) {
+        *self = Self {
+            pending: regions,
+            rect: 0,
+            row: 0,
+        };
+    }
+
+    /// Pass bands of whole rows to `send` until `max_pixels` are used up; the first band is at
+    /// least one row even if that exceeds the budget. Returns whether the frame is complete.
+    pub(crate) fn step<E>(
+        &mut self,
+        max_pixels: u32,
+        mut send: impl FnMut(&Rectangle) -> Result<(), E>,
+    ) -> Result<bool, E> {
+        let mut budget = max_pixels;
+        let mut first = true;
+        while let Some(rect) = self.pending.iter().nth(self.rect) {
+            let left = rect.size.height - self.row;
+            let rows = (budget / rect.size.width).min(left);
+            let rows = if first { rows.max(1) } else { rows };
+            if rows == 0 {
+                break;
+            }
+            let band = Rectangle::new(
+                rect.top_left + Point::new(0, self.row as i32),
+                Size::new(rect.size.width, rows),
+            );
+            send(&band)?;
+            first = false;
+            budget = budget.saturating_sub(rect.size.width * rows);
+            self.row += rows;
+            if self.row == rect.size.height {
+                self.rect += 1;
+                self.row = 0;
+            }
+        }
+        if self.rect == self.pending.len() {
+            self.pending.clear();
+        }
+        Ok(self.is_idle())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    fn cursor(areas: &[Rectangle]) -> FlushCursor {
+        let mut regions = DirtyRegions::new();
+        for area in areas {
+            regions.add(*area);
+        }
+        let mut cursor = FlushCursor::new();
+        cursor.start(regions);
+        cursor
+    }
+
+    /// Run one step of `max_pixels`, returning whether the frame is complete and the bands sent.
+    fn step(cursor: &mut FlushCursor, max_pixels: u32) -> (bool, Vec<Rectangle>) {
+        let mut bands = Vec::new();
+        let done = cursor
+            .step(max_pixels, |band| {
+                bands.push(*band);
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+        (done, bands)
+    }
+
+    #[test]
+    fn step_sends_whole_rows_within_budget() {
+        let mut cursor = cursor(&[rect(0, 0, 4, 5)]);
+        assert_eq!(step(&mut cursor, 11), (false, [rect(0, 0, 4, 2)].into()));
+        assert_eq!(step(&mut cursor, 8), (false, [rect(0, 2, 4, 2)].into()));
+        assert_eq!(step(&mut cursor, 8), (true, [rect(0, 4, 4, 1)].into()));
+        assert!(cursor.is_idle());
+    }
+
+    #[test]
+    fn step_sends_one_row_over_budget() {
+        let mut cursor = cursor(&[rect(0, 0, 4, 2)]);
+        assert_eq!(step(&mut cursor, 3), (false, [rect(0, 0, 4, 1)].into()));
+        assert_eq!(step(&mut cursor, 0), (true, [rect(0, 1, 4, 1)].into()));
+    }
+
+    #[test]
+    fn step_carries_budget_across_areas() {
+        let mut cursor = cursor(&[rect(0, 0, 4, 2), rect(0, 4, 2, 3)]);
+        assert_eq!(
+            step(&mut cursor, 10),
+            (false, [rect(0, 0, 4, 2), rect(0, 4, 2, 1)].into())
+        );
+        assert_eq!(step(&mut cursor, 100), (true, [rect(0, 5, 2, 2)].into()));
+    }
+
+    #[test]
+    fn step_resumes_at_failed_band() {
+        let mut cursor = cursor(&[rect(0, 0, 4, 2), rect(0, 4, 2, 3)]);
+        let mut sent = Vec::new();
+        let res = cursor.step(100, |band| {
+            if band.top_left.y == 4 {
+                return Err(());
+            }
+            sent.push(*band);
+            Ok(())
+        });
+        assert_eq!((res, sent), (Err(()), [rect(0, 0, 4, 2)].into()));
+        assert!(!cursor.is_idle());
+        assert_eq!(step(&mut cursor, 100), (true, [rect(0, 4, 2, 3)].into()));
+    }
+}
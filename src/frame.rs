@@ -0,0 +1,149 @@
+use core::borrow::BorrowMut;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+use crate::*;
+
+/// A frame being drawn on a [`Canvas`], flushed when it ends.
+///
+/// Returned by [`Canvas::begin_frame`]. [`end`](Self::end) flushes and reports the result;
+/// dropping the frame without ending it flushes too, discarding any error.
+pub struct Frame<'c, 'a, T, S, H = &'a mut T>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
+{
+    canvas: &'c mut Canvas<'a, T, S, H>,
+    ended: bool,
+}
+
+impl<'c, 'a, T, S, H> Frame<'c, 'a, T, S, H>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
+{
+    pub(crate) fn new(canvas: &'c mut Canvas<'a, T, S, H>) -> Self {
+        Self {
+            canvas,
+            ended: false,
+        }
+    }
+
+    /// Flush the frame.
+    pub fn end(mut self) -> Result<FrameStats, T::Error> {
+        self.ended = true;
+        self.canvas.flush()
+    }
+
+    /// Blending view of the frame, for drawing with [`Rgba`] colors. It borrows the frame, so
+    /// opaque drawing resumes once it is dropped; [`draw_alpha`](Self::draw_alpha) does both in
+    /// one call.
+    pub fn alpha<const N: usize>(&mut self) -> AlphaCanvas<'_, S::Color, N, S::Storage>
+    where
+        S: HasFramebuffer<S::Color, N>,
+        Rgba<S::Color>: Blend<S::Color>,
+    {
+        self.canvas.alpha()
+    }
+
+    /// Draw `item` blended over the frame, between opaque draws on the frame itself.
+    pub fn draw_alpha<const N: usize, D>(&mut self, item: &D) -> D::Output
+    where
+        S: HasFramebuffer<S::Color, N>,
+        Rgba<S::Color>: Blend<S::Color>,
+        D: Drawable<Color = Rgba<S::Color>>,
+    {
+        match item.draw(&mut self.canvas.alpha()) {
+            Ok(output) => output,
+        }
+    }
+}
+
+impl<T, S, H> Drop for Frame<'_, '_, T, S, H>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
+{
+    fn drop(&mut self) {
+        if !self.ended {
+            let _ = self.canvas.flush();
+        }
+    }
+}
+
+impl<T, S, H> OriginDimensions for Frame<'_, '_, T, S, H>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
+{
+    fn size(&self) -> Size {
+        self.canvas.size()
+    }
+}
+
+impl<T, S, H> DrawTarget for Frame<'_, '_, T, S, H>
+where
+    T: DrawTarget,
+    S: BufferStrategy<Color = T::Color>,
+    H: BorrowMut<T>,
+{
+    type Color = S::Color;
+    type Error = S::Error;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), S::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.canvas.draw_iter(pixels)
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), S::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.canvas.fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), S::Error> {
+        self.canvas.fill_solid(area, color)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self, color: Self::Color) -> Result<(), S::Error> {
+        self.canvas.clear(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    #[test]
+    fn frame_mixes_opaque_and_blended_drawing() {
+        let mut display = Framebuffer::<Rgb888, 6>::new(3, 2);
+        let mut canvas = Canvas::<_, SingleBuffer<Rgb888, 6>>::single_buffered(&mut display);
+        let stats = canvas
+            .frame(|frame| {
+                frame.clear(Rgb888::BLACK).unwrap();
+                frame.draw_alpha(&Pixel(Point::new(1, 0), Rgba::new(Rgb888::WHITE, 128)));
+                Pixel(Point::new(2, 1), Rgb888::RED).draw(frame).unwrap();
+            })
+            .unwrap();
+        assert_eq!((stats.pixels_written, stats.pixels_blended), (7, 1));
+
+        assert_eq!(
+            display.get_pixel(Point::new(1, 0)),
+            Some(Rgb888::new(128, 128, 128))
+        );
+        assert_eq!(display.get_pixel(Point::new(2, 1)), Some(Rgb888::RED));
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb888::BLACK));
+    }
+}
@@ -1,58 +1,599 @@
 use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::Range;
 use embedded_graphics_core::prelude::*;
 use embedded_graphics_core::primitives::*;
 
-pub struct Framebuffer<C: RgbColor, const N: usize> {
-    buf: [C; N],
+use crate::dirty::*;
+use crate::*;
+
+/// Pixel memory behind a [`Framebuffer`]: an owned `[C; N]` by default, or borrowed memory such
+/// as a `&'static mut [C]` placed in a dedicated RAM bank.
+pub trait Storage<C>: AsRef<[C]> + AsMut<[C]> {}
+
+impl<C, B> Storage<C> for B where B: AsRef<[C]> + AsMut<[C]> + ?Sized {}
+
+/// Pixel access a [`Framebuffer`] needs from its memory, by index in row-major order. Any slice
+/// [`Storage`] provides it; [`Packed`] provides it for colors of a few bits per pixel.
+pub trait PixelStorage<C: Copy> {
+    /// Number of pixels the memory holds.
+    fn capacity(&self) -> usize;
+
+    fn get(&self, i: usize) -> C;
+
+    fn set(&mut self, i: usize, color: C);
+
+    /// Set every pixel in `range` to `color`.
+    fn fill(&mut self, range: Range<usize>, color: C) {
+        for i in range {
+            self.set(i, color);
+        }
+    }
+
+    /// Replace every pixel in `range` with `f` applied to its current color.
+    fn update(&mut self, range: Range<usize>, mut f: impl FnMut(C) -> C) {
+        for i in range {
+            let color = self.get(i);
+            self.set(i, f(color));
+        }
+    }
+
+    /// Colors of `range` in order.
+    fn pixels<'a>(&'a self, range: Range<usize>) -> impl Iterator<Item = C> + 'a
+    where
+        C: 'a,
+    {
+        range.map(move |i| self.get(i))
+    }
+
+    /// Copy `range` from `src`, memory of the same layout.
+    fn copy_from(&mut self, src: &Self, range: Range<usize>) {
+        for i in range {
+            self.set(i, src.get(i));
+        }
+    }
+}
+
+impl<C: Copy, B> PixelStorage<C> for B
+where
+    B: Storage<C> + ?Sized,
+{
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    #[inline(always)]
+    fn get(&self, i: usize) -> C {
+        self.as_ref()[i]
+    }
+
+    #[inline(always)]
+    fn set(&mut self, i: usize, color: C) {
+        self.as_mut()[i] = color;
+    }
+
+    // Row-subslice fill = memcpy-class speed
+    #[inline(always)]
+    fn fill(&mut self, range: Range<usize>, color: C) {
+        self.as_mut()[range].fill(color);
+    }
+
+    #[inline(always)]
+    fn update(&mut self, range: Range<usize>, mut f: impl FnMut(C) -> C) {
+        for px in &mut self.as_mut()[range] {
+            *px = f(*px);
+        }
+    }
+
+    #[inline(always)]
+    fn pixels<'a>(&'a self, range: Range<usize>) -> impl Iterator<Item = C> + 'a
+    where
+        C: 'a,
+    {
+        self.as_ref()[range].iter().copied()
+    }
+
+    #[inline(always)]
+    fn copy_from(&mut self, src: &Self, range: Range<usize>) {
+        self.as_mut()[range.clone()].copy_from_slice(&src.as_ref()[range]);
+    }
+}
+
+/// Requested dimensions do not cover exactly the `N` pixels a framebuffer holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeMismatch {
+    pub capacity: usize,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub struct Framebuffer<C: PixelColor, const N: usize, B = [C; N]> {
+    buf: B,
     pub width: u32,
     pub height: u32,
+    stride: u32,
+    origin: Point,
+    dirty: DirtyRegions,
+    _color: PhantomData<C>,
+}
+
+impl<C: PixelColor, const N: usize> Framebuffer<C, N> {
+    /// Framebuffer holding its pixels inline, all set to `color`; works for any color type, e.g.
+    /// `Gray8`. Panics unless `width * height == N`.
+    #[inline]
+    pub const fn filled(color: C, width: u32, height: u32) -> Self {
+        Self::with_storage([color; N], width, height, width)
+    }
 }
 
 impl<C: RgbColor, const N: usize> Framebuffer<C, N> {
+    /// Black framebuffer holding its pixels inline; usable to initialize a `static`.
+    ///
+    /// Panics unless `width * height == N`; see [`sized`](Self::sized) and
+    /// [`try_new`](Self::try_new) for checked alternatives.
     #[inline]
-    pub fn new(width: u32, height: u32) -> Self {
-        debug_assert_eq!(N as u32, width * height, "N must be width*height");
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self::filled(C::BLACK, width, height)
+    }
+
+    /// Like [`new`](Self::new), with rows starting `stride` pixels apart, e.g. padded to the
+    /// alignment a DMA engine needs. Panics unless `stride * height == N` and `stride >= width`.
+    #[inline]
+    pub const fn with_stride(width: u32, height: u32, stride: u32) -> Self {
+        Self::with_storage([C::BLACK; N], width, height, stride)
+    }
+
+    /// `W` x `H` framebuffer; dimensions that do not match `N` fail to compile.
+    pub const fn sized<const W: usize, const H: usize>() -> Self {
+        const { assert!(W * H == N, "N must be W * H") };
+        Self::new(W as u32, H as u32)
+    }
+
+    /// Like [`new`](Self::new), for dimensions only known at runtime.
+    pub const fn try_new(width: u32, height: u32) -> Result<Self, SizeMismatch> {
+        if !fits::<N>(width, height, width) {
+            return Err(SizeMismatch {
+                capacity: N,
+                width,
+                height,
+            });
+        }
+        Ok(Self::new(width, height))
+    }
+
+    /// Black `width` x `height` framebuffer over the leading pixels of `N`; the rest stays unused.
+    pub(crate) fn leading(width: u32, height: u32) -> Self {
+        assert!(
+            width as u64 * height as u64 <= N as u64,
+            "N must hold width * height pixels"
+        );
+        Self::assemble([C::BLACK; N], width, height, width)
+    }
+}
+
+/// Whether `N` pixels hold exactly `height` rows of `width` pixels starting `stride` apart; the
+/// padding after the last row may be left out.
+const fn fits<const N: usize>(width: u32, height: u32, stride: u32) -> bool {
+    let (n, w, h, s) = (N as u64, width as u64, height as u64, stride as u64);
+    s >= w && n <= s * h && (h == 0 || n >= s * (h - 1) + w)
+}
+
+impl<'a, C: RgbColor, const N: usize> Framebuffer<C, N, &'a mut [C; N]> {
+    /// Black framebuffer over `memory`, filled in place so the pixels never pass through the
+    /// stack; e.g. a `static` in a linker section for external RAM.
+    pub fn init(memory: &'a mut MaybeUninit<[C; N]>, width: u32, height: u32) -> Self {
+        let first = memory.as_mut_ptr().cast::<C>();
+        for i in 0..N {
+            // SAFETY: `i` stays within the `N` pixels of the array.
+            unsafe { first.add(i).write(C::BLACK) };
+        }
+        // SAFETY: every pixel was written above.
+        let buf = unsafe { memory.assume_init_mut() };
+        Self::with_storage(buf, width, height, width)
+    }
+}
+
+impl<C: PackedColor, const N: usize, const BYTES: usize> Framebuffer<C, N, Packed<C, [u8; BYTES]>> {
+    /// Black framebuffer packing its `N` pixels into `BYTES` inline bytes; usable to initialize a
+    /// `static`. A `BYTES` that does not fit `N` pixels fails to compile.
+    #[inline]
+    pub const fn packed(width: u32, height: u32) -> Self {
+        Self::packed_with_stride(width, height, width)
+    }
+
+    /// Like [`packed`](Self::packed), with rows starting `stride` pixels apart.
+    pub const fn packed_with_stride(width: u32, height: u32, stride: u32) -> Self {
+        const { assert!(BYTES * 8 == N * C::BITS, "BYTES must hold exactly N pixels") };
+        Self::with_storage(Packed::new([0; BYTES]), width, height, stride)
+    }
+}
+
+impl<C: PixelColor, const N: usize, B> Framebuffer<C, N, B>
+where
+    B: PixelStorage<C>,
+{
+    /// Framebuffer over `buf`, which must hold exactly `N` pixels; its contents are kept.
+    pub fn from_storage(buf: B, width: u32, height: u32) -> Self {
+        Self::from_storage_with_stride(buf, width, height, width)
+    }
+
+    /// Like [`from_storage`](Self::from_storage), with rows starting `stride` pixels apart. The
+    /// last row's padding may be left out, so `buf` can be a window into a larger surface.
+    pub fn from_storage_with_stride(buf: B, width: u32, height: u32, stride: u32) -> Self {
+        assert_eq!(buf.capacity(), N, "storage must hold N pixels");
+        Self::with_storage(buf, width, height, stride)
+    }
+
+    const fn with_storage(buf: B, width: u32, height: u32, stride: u32) -> Self {
+        assert!(
+            fits::<N>(width, height, stride),
+            "N must be stride * height"
+        );
+        Self::assemble(buf, width, height, stride)
+    }
+
+    const fn assemble(buf: B, width: u32, height: u32, stride: u32) -> Self {
+        // Nothing is known about the panel yet, so the first flush sends everything.
+        let all = Rectangle::new(Point::zero(), Size::new(width, height));
         Self {
-            buf: [C::BLACK; N],
+            buf,
             width,
             height,
+            stride,
+            origin: Point::zero(),
+            dirty: DirtyRegions::with_area(all),
+            _color: PhantomData,
         }
     }
 
+    /// Give back the pixel memory.
     #[inline]
-    pub fn iter_colors(&self) -> impl Iterator<Item = C> + '_ {
-        self.buf.iter().copied()
+    pub fn into_storage(self) -> B {
+        self.buf
     }
 
+    /// The pixel memory, e.g. the packed bytes to send to a monochrome panel.
     #[inline]
-    pub fn buf(&self) -> &[C; N] {
+    pub fn storage(&self) -> &B {
         &self.buf
     }
 
+    /// Writes through it are not tracked.
     #[inline]
-    pub(super) fn buf_mut(&mut self) -> &mut [C; N] {
+    pub(crate) fn storage_mut(&mut self) -> &mut B {
         &mut self.buf
     }
 
+    #[inline]
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// Distance in pixels from the start of one row to the start of the next.
+    #[inline]
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    /// Top-left corner of the framebuffer in draw coordinates.
+    #[inline]
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    /// Place the framebuffer at `origin`; drawing outside of the covered area is clipped.
+    #[inline]
+    pub fn set_origin(&mut self, origin: Point) {
+        self.origin = origin;
+    }
+
+    /// Color at `p` (in draw coordinates), `None` outside the framebuffer.
+    #[inline]
+    pub fn get_pixel(&self, p: Point) -> Option<C> {
+        self.index_of(p).map(|i| self.buf.get(i))
+    }
+
+    /// Set the color at `p` (in draw coordinates); points outside are ignored.
+    #[inline]
+    pub fn set_pixel(&mut self, p: Point, color: C) {
+        if let Some(i) = self.index_of(p) {
+            self.buf.set(i, color);
+            self.dirty.add(Rectangle::new(p, Size::new(1, 1)));
+        }
+    }
+
+    /// Window onto `area` (in draw coordinates), clipped to the framebuffer.
+    #[inline]
+    pub fn view(&mut self, area: &Rectangle) -> FramebufferView<'_, C, N, B> {
+        FramebufferView::new(self, area)
+    }
+
+    #[inline]
+    pub fn iter_colors(&self) -> impl Iterator<Item = C> + '_ {
+        (0..self.height as usize).flat_map(move |y| {
+            let start = self.idx(0, y);
+            self.buf.pixels(start..start + self.width as usize)
+        })
+    }
+
+    /// Colors of `area` (in draw coordinates) in row-major order; `area` must lie inside the framebuffer.
+    #[inline]
+    pub fn iter_area(&self, area: &Rectangle) -> impl Iterator<Item = C> + '_ {
+        let x0 = (area.top_left.x - self.origin.x) as usize;
+        let y0 = (area.top_left.y - self.origin.y) as usize;
+        let w = area.size.width as usize;
+        (y0..y0 + area.size.height as usize).flat_map(move |y| {
+            let start = self.idx(x0, y);
+            self.buf.pixels(start..start + w)
+        })
+    }
+
+    /// `size.width * size.height` colors starting at `start` (in draw coordinates), advancing by
+    /// `step_x` along a row and by `step_y` from row to row, each repeated into a `scale` x `scale`
+    /// block. Every visited point must lie inside.
+    #[inline]
+    pub(crate) fn iter_stepped(
+        &self,
+        start: Point,
+        step_x: Point,
+        step_y: Point,
+        size: Size,
+        scale: u32,
+    ) -> impl Iterator<Item = C> + '_ {
+        let stride = self.stride as isize;
+        let delta = |p: Point| p.y as isize * stride + p.x as isize;
+        let base = self.idx(
+            (start.x - self.origin.x) as usize,
+            (start.y - self.origin.y) as usize,
+        ) as isize;
+        let (dx, dy) = (delta(step_x), delta(step_y));
+        let s = scale as isize;
+        let cols = size.width as isize * s;
+        (0..size.height as isize * s).flat_map(move |j| {
+            let row = base + (j / s) * dy;
+            (0..cols).map(move |i| self.buf.get((row + (i / s) * dx) as usize))
+        })
+    }
+
+    /// Areas touched since the last flush.
+    #[inline]
+    pub fn dirty(&self) -> &DirtyRegions {
+        &self.dirty
+    }
+
+    /// Record `area` as changed so the next flush sends it.
+    #[inline]
+    pub fn mark_dirty(&mut self, area: &Rectangle) {
+        let clipped = area.intersection(&self.bounding_box());
+        self.dirty.add(clipped);
+    }
+
+    /// Return the dirty areas and start tracking afresh.
+    #[inline]
+    pub(crate) fn take_dirty(&mut self) -> DirtyRegions {
+        let dirty = self.dirty;
+        self.dirty.clear();
+        dirty
+    }
+
+    /// Mark `areas` dirty again, e.g. the ones a failed flush did not send.
+    #[inline]
+    pub(crate) fn restore_dirty(&mut self, areas: impl Iterator<Item = Rectangle>) {
+        for area in areas {
+            self.dirty.add(area);
+        }
+    }
+
+    /// Spans of `area` (in draw coordinates) whose pixels differ from `other`, a framebuffer of the
+    /// same geometry. Runs on a row separated by at most `max_gap` unchanged pixels are joined.
+    pub(crate) fn diff(&self, other: &Self, area: &Rectangle, max_gap: u32) -> DirtyRegions {
+        let mut changed = DirtyRegions::new();
+        let x0 = (area.top_left.x - self.origin.x) as usize;
+        let y0 = (area.top_left.y - self.origin.y) as usize;
+        let w = area.size.width as usize;
+
+        for y in y0..y0 + area.size.height as usize {
+            let start = self.idx(x0, y);
+            let cur = self.buf.pixels(start..start + w);
+            let refr = other.buf.pixels(start..start + w);
+
+            let mut span: Option<(usize, usize)> = None;
+            for (x, (cur, refr)) in cur.zip(refr).enumerate() {
+                if cur == refr {
+                    continue;
+                }
+                span = match span {
+                    Some((s, e)) if x - e <= max_gap as usize => Some((s, x + 1)),
+                    Some((s, e)) => {
+                        changed.add(self.span_rect(x0 + s, x0 + e, y));
+                        Some((x, x + 1))
+                    }
+                    None => Some((x, x + 1)),
+                };
+            }
+            if let Some((s, e)) = span {
+                changed.add(self.span_rect(x0 + s, x0 + e, y));
+            }
+        }
+        changed
+    }
+
+    #[inline]
+    fn span_rect(&self, x_start: usize, x_end: usize, y: usize) -> Rectangle {
+        Rectangle::new(
+            self.origin + Point::new(x_start as i32, y as i32),
+            Size::new((x_end - x_start) as u32, 1),
+        )
+    }
+
+    /// Copy `area` (in draw coordinates) from a framebuffer of the same geometry.
+    #[inline]
+    pub(crate) fn copy_area_from(&mut self, src: &Self, area: &Rectangle) {
+        let x0 = (area.top_left.x - self.origin.x) as usize;
+        let y0 = (area.top_left.y - self.origin.y) as usize;
+        let w = area.size.width as usize;
+        for y in y0..y0 + area.size.height as usize {
+            let start = self.idx(x0, y);
+            self.buf.copy_from(&src.buf, start..start + w);
+        }
+    }
+
     #[inline]
     fn idx(&self, x: usize, y: usize) -> usize {
-        y * self.width as usize + x
+        y * self.stride as usize + x
+    }
+
+    /// Buffer index of a point in draw coordinates, `None` if it falls outside.
+    #[inline(always)]
+    pub(crate) fn index_of(&self, p: Point) -> Option<usize> {
+        let x = p.x.wrapping_sub(self.origin.x) as u32;
+        let y = p.y.wrapping_sub(self.origin.y) as u32;
+        if x < self.width && y < self.height {
+            Some(self.idx(x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+}
+
+impl<C: PixelColor, const N: usize, B> Framebuffer<C, N, B>
+where
+    B: Storage<C>,
+{
+    /// Pixels of row `y`, counted from the top of the framebuffer.
+    #[inline]
+    pub fn row(&self, y: u32) -> &[C] {
+        assert!(y < self.height, "row outside the framebuffer");
+        let start = self.idx(0, y as usize);
+        &self.buf()[start..start + self.width as usize]
+    }
+
+    /// Mutable pixels of row `y`, counted from the top of the framebuffer; the row is marked dirty.
+    #[inline]
+    pub fn row_mut(&mut self, y: u32) -> &mut [C] {
+        assert!(y < self.height, "row outside the framebuffer");
+        self.mark_dirty(&self.row_rect(y));
+        let start = self.idx(0, y as usize);
+        let w = self.width as usize;
+        &mut self.buf_mut()[start..start + w]
+    }
+
+    /// Mutable rows from top to bottom; the whole framebuffer is marked dirty.
+    #[inline]
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [C]> + '_ {
+        let (w, stride) = (self.width as usize, self.stride as usize);
+        self.as_mut_slice()
+            .chunks_mut(stride)
+            .map(move |row| &mut row[..w])
+    }
+
+    /// Pixels in row-major order, rows [`stride`](Self::stride) apart.
+    #[inline]
+    pub fn as_slice(&self) -> &[C] {
+        &self.buf()[..self.used()]
+    }
+
+    /// Mutable pixels in row-major order, rows [`stride`](Self::stride) apart; the whole
+    /// framebuffer is marked dirty.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [C] {
+        self.mark_dirty(&self.bounding_box());
+        let used = self.used();
+        &mut self.buf_mut()[..used]
+    }
+
+    /// Pixels up to the end of the last row, leaving out memory past it, e.g. the spare pixels
+    /// of a [`LineBuffer`](crate::LineBuffer) band.
+    #[inline]
+    fn used(&self) -> usize {
+        (self.stride as usize * self.height as usize).min(self.buf().len())
+    }
+
+    /// Row `y`, counted from the top, in draw coordinates.
+    #[inline]
+    fn row_rect(&self, y: u32) -> Rectangle {
+        Rectangle::new(
+            self.origin + Point::new(0, y as i32),
+            Size::new(self.width, 1),
+        )
+    }
+
+    /// Memory of `count` rows from local row `y`, ending after the last row's visible pixels.
+    #[inline]
+    pub(crate) fn rows_memory(&self, y: u32, count: u32) -> &[C] {
+        let start = self.idx(0, y as usize);
+        let end = self.idx(self.width as usize, (y + count - 1) as usize);
+        &self.buf()[start..end]
+    }
+
+    #[inline]
+    pub fn buf(&self) -> &[C] {
+        self.buf.as_ref()
+    }
+
+    #[inline]
+    pub(super) fn buf_mut(&mut self) -> &mut [C] {
+        self.buf.as_mut()
+    }
+}
+
+impl<C: RgbColor, const N: usize, B> Framebuffer<C, N, B>
+where
+    B: PixelStorage<C>,
+{
+    /// FNV-1a hash of local row `y`.
+    pub(crate) fn row_hash(&self, y: usize) -> u32 {
+        let start = self.idx(0, y);
+        self.buf
+            .pixels(start..start + self.width as usize)
+            .fold(0x811c_9dc5, |h: u32, c| {
+                let v = (c.r() as u32) << 16 | (c.g() as u32) << 8 | c.b() as u32;
+                (h ^ v).wrapping_mul(0x0100_0193)
+            })
     }
 }
 
-impl<C, const N: usize> OriginDimensions for Framebuffer<C, N>
+impl<C: WireColor, const N: usize, B> Framebuffer<C, N, B>
 where
-    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Pixels as the bytes sent to the panel, row-major, ready for a single SPI/DMA write. Rows
+    /// are [`stride`](Framebuffer::stride) pixels apart.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        let pixels = self.as_slice();
+        // SAFETY: `WireColor` types are transparent byte arrays of `C::BYTES` bytes.
+        unsafe { core::slice::from_raw_parts(pixels.as_ptr().cast(), pixels.len() * C::BYTES) }
+    }
+
+    /// Mutable view of [`as_bytes`](Self::as_bytes). Writes through it are not tracked; call
+    /// [`mark_dirty`](Self::mark_dirty) for the areas changed.
+    #[inline]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        let used = self.used();
+        let pixels = &mut self.buf_mut()[..used];
+        // SAFETY: as above, and every byte pattern is a valid `WireColor` value.
+        unsafe { core::slice::from_raw_parts_mut(pixels.as_mut_ptr().cast(), used * C::BYTES) }
+    }
+}
+
+impl<C, const N: usize, B> Dimensions for Framebuffer<C, N, B>
+where
+    C: PixelColor,
+    B: PixelStorage<C>,
 {
     #[inline(always)]
-    fn size(&self) -> Size {
-        Size::new(self.width, self.height)
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(self.origin, self.size())
     }
 }
 
-impl<C, const N: usize> DrawTarget for Framebuffer<C, N>
+impl<C, const N: usize, B> DrawTarget for Framebuffer<C, N, B>
 where
-    C: RgbColor,
+    C: PixelColor,
+    B: PixelStorage<C>,
 {
     type Error = Infallible;
     type Color = C;
@@ -62,14 +603,14 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
+        let mut touched = Touched::new();
         for Pixel(p, color) in pixels {
-            let x = p.x as u32;
-            let y = p.y as u32;
-            if x < self.width && y < self.height {
-                let idx = self.idx(x as usize, y as usize);
-                self.buf[idx] = color;
+            if let Some(idx) = self.index_of(p) {
+                self.buf.set(idx, color);
+                touched.add(p);
             }
         }
+        self.dirty.add(touched.area());
         Ok(())
     }
 
@@ -84,6 +625,7 @@ where
         }
 
         let clipped = area.intersection(&self.bounding_box());
+        self.dirty.add(clipped);
         if clipped.size.width == 0 || clipped.size.height == 0 {
             // consume to honor e-g expectations
             for _ in 0..area.size.width * area.size.height {
@@ -102,7 +644,8 @@ where
         let y1 = y0 + area.size.height as i32;
         let cy0 = clipped.top_left.y;
         let cy1 = cy0 + clipped.size.height as i32;
-        let cx0 = clipped.top_left.x as usize;
+        let cx0 = (clipped.top_left.x - self.origin.x) as usize;
+        let oy = self.origin.y;
 
         for y in y0..y1 {
             // discard left outside segment
@@ -111,14 +654,9 @@ where
             }
 
             if y >= cy0 && y < cy1 {
-                let row_start = self.idx(cx0, y as usize);
-                for dst in &mut self.buf[row_start..row_start + mid_w] {
-                    if let Some(c) = it.next() {
-                        *dst = c;
-                    } else {
-                        break;
-                    }
-                }
+                let row_start = self.idx(cx0, (y - oy) as usize);
+                self.buf
+                    .update(row_start..row_start + mid_w, |old| it.next().unwrap_or(old));
             } else {
                 // row fully outside vertically; still consume the inside span
                 for _ in 0..mid_w {
@@ -141,23 +679,95 @@ where
         if clipped.size.width == 0 || clipped.size.height == 0 {
             return Ok(());
         }
+        self.dirty.add(clipped);
 
-        let x0 = clipped.top_left.x as usize;
-        let y0 = clipped.top_left.y as usize;
+        let x0 = (clipped.top_left.x - self.origin.x) as usize;
+        let y0 = (clipped.top_left.y - self.origin.y) as usize;
         let span_w = clipped.size.width as usize;
         let y_end = y0 + clipped.size.height as usize;
 
-        // Row-subslice fill = memcpy-class speed
         for y in y0..y_end {
             let start = self.idx(x0, y);
-            self.buf[start..start + span_w].fill(color);
+            self.buf.fill(start..start + span_w, color);
         }
         Ok(())
     }
 
     #[inline(always)]
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        self.buf.fill(color);
-        Ok(())
+        if self.stride == self.width {
+            self.buf.fill(0..N, color);
+            self.dirty.add(self.bounding_box());
+            return Ok(());
+        }
+        // Leave the padding alone; it may belong to a larger surface.
+        self.fill_solid(&self.bounding_box(), color)
+    }
+}
+
+/// Copy `area` from `bufs[src]` into `bufs[dst]`.
+pub(crate) fn copy_between<C, const N: usize, B>(
+    bufs: &mut [Framebuffer<C, N, B>],
+    dst: usize,
+    src: usize,
+    area: &Rectangle,
+) where
+    C: PixelColor,
+    B: PixelStorage<C>,
+{
+    debug_assert_ne!(dst, src);
+    let (lo, hi) = bufs.split_at_mut(dst.max(src));
+    let (d, s) = if dst < src {
+        (&mut lo[dst], &hi[0])
+    } else {
+        (&mut hi[0], &lo[src])
+    };
+    d.copy_area_from(s, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    #[test]
+    fn spare_pixels_stay_hidden() {
+        // Two rows of 4 in 10 pixels, as a line buffer band.
+        let mut band = Framebuffer::<Rgb565, 10>::leading(4, 2);
+        assert_eq!(band.rows_mut().map(|row| row.len()).sum::<usize>(), 8);
+        assert_eq!(band.as_slice().len(), 8);
+        assert_eq!(band.as_mut_slice().len(), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "row outside the framebuffer")]
+    fn row_past_the_bottom() {
+        Framebuffer::<Rgb565, 10>::leading(4, 2).row(2);
+    }
+
+    #[test]
+    fn diff_joins_runs_up_to_max_gap() {
+        let reference = Framebuffer::<Rgb565, 8>::new(8, 1);
+        let mut current = Framebuffer::<Rgb565, 8>::new(8, 1);
+        // Changed pixels at 0, 3 and 7: gaps of 2 and 3 unchanged pixels.
+        for x in [0, 3, 7] {
+            current.set_pixel(Point::new(x, 0), Rgb565::RED);
+        }
+        let row = current.bounding_box();
+        let span = |x0, x1| Rectangle::with_corners(Point::new(x0, 0), Point::new(x1, 0));
+
+        assert!(
+            current
+                .diff(&reference, &row, 2)
+                .iter()
+                .eq([span(0, 3), span(7, 7)])
+        );
+        assert!(current.diff(&reference, &row, 3).iter().eq([span(0, 7)]));
+        assert!(
+            current
+                .diff(&reference, &row, 1)
+                .iter()
+                .eq([span(0, 0), span(3, 3), span(7, 7)])
+        );
     }
 }
@@ -2,10 +2,39 @@
 
 mod alpha;
 mod canvas;
+mod convert;
+mod dirty;
+mod frame;
 mod framebuffer;
+mod lut;
+pub mod mock;
+mod output;
+mod packed;
+mod panel;
 mod rgba;
+mod shared;
+mod stats;
+mod tiled;
+mod transfer;
+mod transition;
+mod view;
+mod wire;
 
 pub use alpha::*;
 pub use canvas::*;
-use framebuffer::*;
+pub use convert::*;
+pub use dirty::*;
+pub use frame::*;
+pub use framebuffer::*;
+pub use lut::*;
+pub use output::*;
+pub use packed::*;
+pub use panel::*;
 pub use rgba::*;
+pub use shared::*;
+pub use stats::*;
+pub use tiled::*;
+pub use transfer::*;
+pub use transition::*;
+pub use view::*;
+pub use wire::*;
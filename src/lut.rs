@@ -0,0 +1,257 @@
+use embedded_graphics_core::pixelcolor::*;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+/// RGB colors that can be rebuilt from channels at their native depth.
+pub trait FromChannels: RgbColor {
+    fn from_channels(r: u8, g: u8, b: u8) -> Self;
+}
+
+macro_rules! impl_from_channels {
+    ($($ty:ty),*) => {
+        $(impl FromChannels for $ty {
+            #[inline(always)]
+            fn from_channels(r: u8, g: u8, b: u8) -> Self {
+                Self::new(r, g, b)
+            }
+        })*
+    };
+}
+
+impl_from_channels!(
+    Rgb555, Rgb565, Rgb666, Rgb888, Bgr555, Bgr565, Bgr666, Bgr888
+);
+
+/// Per-channel lookup tables applied to every outgoing pixel, at the color's native depth.
+///
+/// Tables are built from functions over the full `0..=255` range, so the same curve works for
+/// any color type; e.g. `Lut::from_fn(|v| GAMMA[v as usize])` for panel calibration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lut<C> {
+    r: [u8; 256],
+    g: [u8; 256],
+    b: [u8; 256],
+    _color: core::marker::PhantomData<C>,
+}
+
+impl<C> Lut<C>
+where
+    C: FromChannels,
+{
+    /// Tables that leave every pixel unchanged.
+    pub fn identity() -> Self {
+        Self::from_fn(|v| v)
+    }
+
+    /// Apply the same curve to all channels.
+    pub fn from_fn(f: impl Fn(u8) -> u8) -> Self {
+        Self::per_channel(&f, &f, &f)
+    }
+
+    /// Apply a separate curve to each channel.
+    pub fn per_channel(r: impl Fn(u8) -> u8, g: impl Fn(u8) -> u8, b: impl Fn(u8) -> u8) -> Self {
+        Self {
+            r: table(C::MAX_R, r),
+            g: table(C::MAX_G, g),
+            b: table(C::MAX_B, b),
+            _color: core::marker::PhantomData,
+        }
+    }
+
+    /// Scale all channels by `level / 255`.
+    pub fn brightness(level: u8) -> Self {
+        Self::from_fn(|v| scale(v, level))
+    }
+
+    /// Scale each channel separately, e.g. `tint(255, 180, 120)` for a warm night mode.
+    pub fn tint(r: u8, g: u8, b: u8) -> Self {
+        Self::per_channel(|v| scale(v, r), |v| scale(v, g), |v| scale(v, b))
+    }
+
+    /// Swap dark and light.
+    pub fn invert() -> Self {
+        Self::from_fn(|v| 255 - v)
+    }
+
+    /// Tables applying `self`, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut out = self.clone();
+        // Entries past the channel's maximum stay zero, as `table` leaves them.
+        for (t, n, max) in [
+            (&mut out.r, &next.r, C::MAX_R),
+            (&mut out.g, &next.g, C::MAX_G),
+            (&mut out.b, &next.b, C::MAX_B),
+        ] {
+            for v in t.iter_mut().take(max as usize + 1) {
+                *v = n[*v as usize];
+            }
+        }
+        out
+    }
+
+    #[inline(always)]
+    pub fn apply(&self, c: C) -> C {
+        C::from_channels(
+            self.r[c.r() as usize],
+            self.g[c.g() as usize],
+            self.b[c.b() as usize],
+        )
+    }
+}
+
+/// Table mapping native channel values `0..=max` through `f`, which works on `0..=255`.
+fn table(max: u8, f: impl Fn(u8) -> u8) -> [u8; 256] {
+    let max = max as u32;
+    let mut t = [0; 256];
+    for (v, out) in t.iter_mut().enumerate().take(max as usize + 1) {
+        let wide = (v as u32 * 255 + max / 2) / max;
+        *out = ((f(wide as u8) as u32 * max + 127) / 255) as u8;
+    }
+    t
+}
+
+#[inline]
+fn scale(v: u8, level: u8) -> u8 {
+    ((v as u32 * level as u32 + 127) / 255) as u8
+}
+
+/// Passes everything drawn through a [`Lut`] before it reaches `target`.
+///
+/// Put it in front of the display to grade every flush without touching framebuffer contents.
+pub struct Mapped<T>
+where
+    T: DrawTarget,
+{
+    target: T,
+    lut: Lut<T::Color>,
+}
+
+impl<T> Mapped<T>
+where
+    T: DrawTarget,
+    T::Color: FromChannels,
+{
+    pub fn new(target: T, lut: Lut<T::Color>) -> Self {
+        Self { target, lut }
+    }
+
+    pub fn lut(&self) -> &Lut<T::Color> {
+        &self.lut
+    }
+
+    pub fn set_lut(&mut self, lut: Lut<T::Color>) {
+        self.lut = lut;
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.target
+    }
+
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> T {
+        self.target
+    }
+}
+
+impl<T> Dimensions for Mapped<T>
+where
+    T: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<T> DrawTarget for Mapped<T>
+where
+    T: DrawTarget,
+    T::Color: FromChannels,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let lut = &self.lut;
+        self.target
+            .draw_iter(pixels.into_iter().map(|Pixel(p, c)| Pixel(p, lut.apply(c))))
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let lut = &self.lut;
+        self.target
+            .fill_contiguous(area, colors.into_iter().map(|c| lut.apply(c)))
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let color = self.lut.apply(color);
+        self.target.fill_solid(area, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::raw::RawU16;
+
+    /// Every `Rgb565` color.
+    fn all_rgb565() -> impl Iterator<Item = Rgb565> {
+        (0..=u16::MAX).map(|raw| Rgb565::from(RawU16::new(raw)))
+    }
+
+    #[test]
+    fn identity_round_trips_every_value() {
+        let lut = Lut::<Rgb565>::identity();
+        assert!(all_rgb565().all(|c| lut.apply(c) == c));
+
+        let lut = Lut::<Rgb666>::identity();
+        for v in 0..=63 {
+            let c = Rgb666::new(v, v, v);
+            assert_eq!(lut.apply(c), c);
+        }
+
+        let lut = Lut::<Rgb888>::identity();
+        for v in 0..=255 {
+            let c = Rgb888::new(v, v, v);
+            assert_eq!(lut.apply(c), c);
+        }
+    }
+
+    #[test]
+    fn full_and_zero_brightness() {
+        assert_eq!(Lut::<Rgb565>::brightness(255), Lut::identity());
+        let lut = Lut::<Rgb565>::brightness(0);
+        assert!(all_rgb565().all(|c| lut.apply(c) == Rgb565::BLACK));
+    }
+
+    #[test]
+    fn invert_mirrors_native_levels() {
+        let lut = Lut::<Rgb565>::invert();
+        for c in all_rgb565() {
+            let expected = Rgb565::new(31 - c.r(), 63 - c.g(), 31 - c.b());
+            assert_eq!(lut.apply(c), expected);
+        }
+        assert_eq!(lut.then(&Lut::invert()), Lut::identity());
+    }
+
+    #[test]
+    fn then_applies_in_order() {
+        let dim = Lut::<Rgb565>::tint(255, 128, 64);
+        let invert = Lut::<Rgb565>::invert();
+        let both = dim.then(&invert);
+        assert!(all_rgb565().all(|c| both.apply(c) == invert.apply(dim.apply(c))));
+        // Inverting first lets the tint darken the inverted image instead.
+        assert_ne!(both, invert.then(&dim));
+    }
+}
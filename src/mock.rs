@@ -0,0 +1,183 @@
+//! Host-side stand-ins for display hardware, for exercising flush paths in tests.
+
+use core::task::Poll;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+use crate::*;
+
+/// [`Transfer`] that completes after a fixed number of polls and keeps a copy of what was sent.
+pub struct MockTransfer<C, const N: usize>
+where
+    C: PixelColor,
+{
+    pixels: [C; N],
+    len: usize,
+    area: Rectangle,
+    latency: u32,
+    remaining: u32,
+    started: u32,
+}
+
+impl<C, const N: usize> MockTransfer<C, N>
+where
+    C: PixelColor,
+{
+    /// `latency` is the number of `Pending` polls reported before each transfer completes.
+    pub fn new(fill: C, latency: u32) -> Self {
+        Self {
+            pixels: [fill; N],
+            len: 0,
+            area: Rectangle::zero(),
+            latency,
+            remaining: 0,
+            started: 0,
+        }
+    }
+
+    /// Pixels of the last transfer.
+    pub fn pixels(&self) -> &[C] {
+        &self.pixels[..self.len]
+    }
+
+    /// Area of the last transfer.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Number of transfers started so far.
+    pub fn started(&self) -> u32 {
+        self.started
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.remaining > 0
+    }
+}
+
+impl<C, const N: usize> Transfer<C> for MockTransfer<C, N>
+where
+    C: PixelColor,
+{
+    type Error = ();
+
+    unsafe fn start(&mut self, area: &Rectangle, pixels: &[C]) -> Result<(), ()> {
+        // SAFETY: the pixels are copied before returning.
+        unsafe { self.start_strided(area, pixels, area.size.width) }.map(|_| ())
+    }
+
+    /// Copies the rows right away and keeps them packed, like a DMA engine skipping the padding.
+    unsafe fn start_strided(
+        &mut self,
+        area: &Rectangle,
+        pixels: &[C],
+        stride: u32,
+    ) -> Result<u32, ()> {
+        let w = area.size.width as usize;
+        let len = w * area.size.height as usize;
+        if self.remaining > 0 || len > N {
+            return Err(());
+        }
+        if w > 0 {
+            for (dst, src) in self.pixels[..len]
+                .chunks_exact_mut(w)
+                .zip(pixels.chunks(stride as usize))
+            {
+                dst.copy_from_slice(&src[..w]);
+            }
+        }
+        self.len = len;
+        self.area = *area;
+        self.remaining = self.latency;
+        self.started += 1;
+        Ok(area.size.height)
+    }
+
+    fn poll(&mut self) -> Poll<Result<(), ()>> {
+        if self.remaining == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        self.remaining -= 1;
+        Poll::Pending
+    }
+}
+
+/// One call recorded by [`RecordingSink`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SinkOp {
+    Window(Rectangle),
+    /// Pixel bytes written since the previous window; consecutive writes are merged.
+    Pixels(usize),
+}
+
+/// [`PanelSink`] that records the command stream, up to `OPS` operations and `BYTES` pixel bytes.
+///
+/// Running out of room fails the flush with `Err(())`.
+pub struct RecordingSink<const OPS: usize, const BYTES: usize> {
+    ops: [SinkOp; OPS],
+    op_count: usize,
+    bytes: [u8; BYTES],
+    byte_count: usize,
+}
+
+impl<const OPS: usize, const BYTES: usize> RecordingSink<OPS, BYTES> {
+    pub const fn new() -> Self {
+        Self {
+            ops: [SinkOp::Pixels(0); OPS],
+            op_count: 0,
+            bytes: [0; BYTES],
+            byte_count: 0,
+        }
+    }
+
+    /// Recorded operations, oldest first.
+    pub fn ops(&self) -> &[SinkOp] {
+        &self.ops[..self.op_count]
+    }
+
+    /// Every pixel byte written, in order.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.byte_count]
+    }
+
+    /// Forget everything recorded so far.
+    pub fn clear(&mut self) {
+        self.op_count = 0;
+        self.byte_count = 0;
+    }
+
+    fn push(&mut self, op: SinkOp) -> Result<(), ()> {
+        let slot = self.ops.get_mut(self.op_count).ok_or(())?;
+        *slot = op;
+        self.op_count += 1;
+        Ok(())
+    }
+}
+
+impl<const OPS: usize, const BYTES: usize> Default for RecordingSink<OPS, BYTES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const OPS: usize, const BYTES: usize> PanelSink for RecordingSink<OPS, BYTES> {
+    type Error = ();
+
+    fn set_window(&mut self, area: &Rectangle) -> Result<(), ()> {
+        self.push(SinkOp::Window(*area))
+    }
+
+    fn write_pixels(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let end = self.byte_count + bytes.len();
+        self.bytes
+            .get_mut(self.byte_count..end)
+            .ok_or(())?
+            .copy_from_slice(bytes);
+        self.byte_count = end;
+        match self.op_count.checked_sub(1).map(|i| &mut self.ops[i]) {
+            Some(SinkOp::Pixels(len)) => *len += bytes.len(),
+            _ => self.push(SinkOp::Pixels(bytes.len()))?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+use crate::*;
+
+/// Clockwise rotation of the image on the panel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// How the drawn image is laid out on the panel: rotated first, then mirrored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    /// Flip left and right on the panel.
+    pub mirror_x: bool,
+    /// Flip top and bottom on the panel.
+    pub mirror_y: bool,
+}
+
+impl Orientation {
+    pub const fn rotated(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            mirror_x: false,
+            mirror_y: false,
+        }
+    }
+
+    pub const fn mirrored(self, mirror_x: bool, mirror_y: bool) -> Self {
+        Self {
+            rotation: self.rotation,
+            mirror_x,
+            mirror_y,
+        }
+    }
+
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Size on the panel of an image of `size`.
+    #[inline]
+    pub fn apply_size(&self, size: Size) -> Size {
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => size,
+            Rotation::Deg90 | Rotation::Deg270 => Size::new(size.height, size.width),
+        }
+    }
+
+    /// Panel position of point `p` of an image of `size`.
+    pub fn apply(&self, p: Point, size: Size) -> Point {
+        let (w, h) = (size.width as i32, size.height as i32);
+        let p = match self.rotation {
+            Rotation::Deg0 => p,
+            Rotation::Deg90 => Point::new(h - 1 - p.y, p.x),
+            Rotation::Deg180 => Point::new(w - 1 - p.x, h - 1 - p.y),
+            Rotation::Deg270 => Point::new(p.y, w - 1 - p.x),
+        };
+        let panel = self.apply_size(size);
+        Point::new(
+            if self.mirror_x {
+                panel.width as i32 - 1 - p.x
+            } else {
+                p.x
+            },
+            if self.mirror_y {
+                panel.height as i32 - 1 - p.y
+            } else {
+                p.y
+            },
+        )
+    }
+
+    /// Image position shown at panel point `p`, for an image of `size`.
+    pub fn invert(&self, p: Point, size: Size) -> Point {
+        let (w, h) = (size.width as i32, size.height as i32);
+        let panel = self.apply_size(size);
+        let p = Point::new(
+            if self.mirror_x {
+                panel.width as i32 - 1 - p.x
+            } else {
+                p.x
+            },
+            if self.mirror_y {
+                panel.height as i32 - 1 - p.y
+            } else {
+                p.y
+            },
+        );
+        match self.rotation {
+            Rotation::Deg0 => p,
+            Rotation::Deg90 => Point::new(p.y, h - 1 - p.x),
+            Rotation::Deg180 => Point::new(w - 1 - p.x, h - 1 - p.y),
+            Rotation::Deg270 => Point::new(w - 1 - p.y, p.x),
+        }
+    }
+
+    /// Panel rectangle covered by `area` of an image of `size`.
+    pub fn apply_rect(&self, area: &Rectangle, size: Size) -> Rectangle {
+        match area.bottom_right() {
+            Some(br) => {
+                Rectangle::with_corners(self.apply(area.top_left, size), self.apply(br, size))
+            }
+            None => Rectangle::zero(),
+        }
+    }
+}
+
+/// Destination of a flush: the target, the part of the drawn frame it shows, where that frame
+/// lands on the target, in which orientation and at which integer scale.
+pub struct Output<'a, T> {
+    target: &'a mut T,
+    frame: Rectangle,
+    origin: Point,
+    orientation: Orientation,
+    scale: u32,
+}
+
+impl<'a, T> Output<'a, T>
+where
+    T: DrawTarget,
+{
+    /// Show `frame` (in draw coordinates) at the target's top-left corner, unrotated.
+    pub fn new(target: &'a mut T, frame: Rectangle) -> Self {
+        Self {
+            target,
+            frame,
+            origin: Point::zero(),
+            orientation: Orientation::default(),
+            scale: 1,
+        }
+    }
+
+    /// Place the frame's top-left corner at `origin` on the target.
+    pub fn with_origin(mut self, origin: Point) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Replicate every pixel into a `scale` x `scale` block on the target. Panics if `scale` is 0.
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        assert!(scale > 0, "scale must be at least 1");
+        self.scale = scale;
+        self
+    }
+
+    /// Part of the drawn frame shown on the target.
+    #[inline]
+    pub fn frame(&self) -> Rectangle {
+        self.frame
+    }
+
+    /// The same output showing `frame` instead, e.g. a viewport into a larger surface.
+    pub fn reframe(&mut self, frame: Rectangle) -> Output<'_, T> {
+        Output {
+            target: self.target,
+            frame,
+            origin: self.origin,
+            orientation: self.orientation,
+            scale: self.scale,
+        }
+    }
+}
+
+impl<T> Output<'_, T>
+where
+    T: DrawTarget,
+{
+    /// Send `area` (in draw coordinates) of `fb`; anything outside the frame is skipped.
+    pub fn send<const N: usize, B>(
+        &mut self,
+        fb: &Framebuffer<T::Color, N, B>,
+        area: &Rectangle,
+    ) -> Result<(), T::Error>
+    where
+        B: PixelStorage<T::Color>,
+    {
+        let area = area
+            .intersection(&self.frame)
+            .intersection(&fb.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+
+        if self.orientation.is_identity() && self.scale == 1 {
+            let dest = Rectangle::new(area.top_left - self.frame.top_left + self.origin, area.size);
+            return self.target.fill_contiguous(&dest, fb.iter_area(&area));
+        }
+
+        let walk = self.walk(&area);
+        let colors = fb.iter_stepped(walk.start, walk.step_x, walk.step_y, walk.size, self.scale);
+        self.target.fill_contiguous(&walk.dest, colors)
+    }
+
+    /// Send the dirty areas of `fb` and clear them. Areas not sent stay dirty if the target fails.
+    pub(crate) fn send_dirty<const N: usize, B>(
+        &mut self,
+        fb: &mut Framebuffer<T::Color, N, B>,
+    ) -> Result<(), T::Error>
+    where
+        B: PixelStorage<T::Color>,
+    {
+        let dirty = fb.take_dirty();
+        for (i, area) in dirty.iter().enumerate() {
+            if let Err(e) = self.send(fb, &area) {
+                fb.restore_dirty(dirty.iter().skip(i));
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Send `area` (in draw coordinates) with every color computed from its point by `pixel`.
+    pub(crate) fn send_with<F>(&mut self, area: &Rectangle, pixel: F) -> Result<(), T::Error>
+    where
+        F: Fn(Point) -> T::Color,
+    {
+        let area = area.intersection(&self.frame);
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+
+        if self.orientation.is_identity() && self.scale == 1 {
+            let dest = Rectangle::new(area.top_left - self.frame.top_left + self.origin, area.size);
+            return self.target.fill_contiguous(&dest, area.points().map(pixel));
+        }
+
+        let walk = self.walk(&area);
+        let s = self.scale as i32;
+        let cols = walk.size.width as i32 * s;
+        let pixel = &pixel;
+        let colors = (0..walk.size.height as i32 * s).flat_map(move |j| {
+            let row = walk.start + walk.step_y * (j / s);
+            (0..cols).map(move |i| pixel(row + walk.step_x * (i / s)))
+        });
+        self.target.fill_contiguous(&walk.dest, colors)
+    }
+
+    /// Walk the panel rectangle covering `area` row by row and find the matching draw points.
+    fn walk(&self, area: &Rectangle) -> Walk {
+        let local = Rectangle::new(area.top_left - self.frame.top_left, area.size);
+        let size = self.frame.size;
+        let panel = self.orientation.apply_rect(&local, size);
+        let start = self.orientation.invert(panel.top_left, size);
+        let step_x = self
+            .orientation
+            .invert(panel.top_left + Point::new(1, 0), size)
+            - start;
+        let step_y = self
+            .orientation
+            .invert(panel.top_left + Point::new(0, 1), size)
+            - start;
+        Walk {
+            dest: Rectangle::new(
+                panel.top_left * self.scale as i32 + self.origin,
+                panel.size * self.scale,
+            ),
+            start: start + self.frame.top_left,
+            step_x,
+            step_y,
+            size: panel.size,
+        }
+    }
+}
+
+/// Destination of a send and the draw points feeding it: `size` points from `start`, advancing by
+/// `step_x` along a panel row and by `step_y` from row to row.
+#[derive(Clone, Copy)]
+struct Walk {
+    dest: Rectangle,
+    start: Point,
+    step_x: Point,
+    step_y: Point,
+    size: Size,
+}
@@ -0,0 +1,273 @@
+use core::marker::PhantomData;
+use core::ops::Range;
+use embedded_graphics_core::pixelcolor::raw::*;
+use embedded_graphics_core::prelude::*;
+
+use crate::*;
+
+/// Colors of 1, 2, 4 or 8 bits per pixel, such as `BinaryColor`, `Gray2` and `Gray4`, that can
+/// be kept in [`Packed`] memory.
+pub trait PackedColor: PixelColor {
+    /// Bits per pixel.
+    const BITS: usize;
+
+    fn to_bits(self) -> u8;
+
+    fn from_bits(bits: u8) -> Self;
+}
+
+impl<C> PackedColor for C
+where
+    C: PixelColor + From<C::Raw>,
+    C::Raw: RawData<Storage = u8> + From<C>,
+{
+    const BITS: usize = C::Raw::BITS_PER_PIXEL;
+
+    #[inline(always)]
+    fn to_bits(self) -> u8 {
+        C::Raw::from(self).into_inner()
+    }
+
+    #[inline(always)]
+    fn from_bits(bits: u8) -> Self {
+        C::from(C::Raw::from(bits))
+    }
+}
+
+/// Bit-packed pixel memory over the bytes `M`, for a [`Framebuffer`] of [`PackedColor`]s.
+///
+/// Pixels follow each other as one bit stream, the leftmost in the most significant bits of a
+/// byte. Most monochrome and e-paper controllers expect every row to start on a byte boundary;
+/// pick a [`stride`](Framebuffer::packed_with_stride) that is a multiple of `8 / C::BITS`.
+pub struct Packed<C, M> {
+    bytes: M,
+    _color: PhantomData<C>,
+}
+
+impl<C: PackedColor, M> Packed<C, M> {
+    const MASK: u8 = ((1u16 << C::BITS) - 1) as u8;
+    const PER_BYTE: usize = 8 / C::BITS;
+
+    /// Pixels stored in `bytes`, which keep their contents; zero bytes are black.
+    pub const fn new(bytes: M) -> Self {
+        const { assert!(8 % C::BITS == 0, "C must have 1, 2, 4 or 8 bits") };
+        Self {
+            bytes,
+            _color: PhantomData,
+        }
+    }
+
+    /// Give back the bytes.
+    #[inline]
+    pub fn into_inner(self) -> M {
+        self.bytes
+    }
+
+    /// Byte holding pixel `i` and the shift of its bits within it.
+    #[inline(always)]
+    fn locate(i: usize) -> (usize, u32) {
+        let bit = i * C::BITS;
+        (bit / 8, (8 - C::BITS - bit % 8) as u32)
+    }
+
+    /// Split pixel `range` into a head of single pixels, a run of whole bytes and a tail of
+    /// single pixels.
+    #[inline]
+    fn split(range: Range<usize>) -> (Range<usize>, Range<usize>, Range<usize>) {
+        let head_end = range.start.next_multiple_of(Self::PER_BYTE).min(range.end);
+        let tail_start = (range.end - range.end % Self::PER_BYTE).max(head_end);
+        (
+            range.start..head_end,
+            head_end / Self::PER_BYTE..tail_start / Self::PER_BYTE,
+            tail_start..range.end,
+        )
+    }
+}
+
+impl<C, M> Packed<C, M>
+where
+    M: AsRef<[u8]>,
+{
+    /// The packed bytes, ready to send to the panel.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl<C, M> PixelStorage<C> for Packed<C, M>
+where
+    C: PackedColor,
+    M: AsRef<[u8]> + AsMut<[u8]>,
+{
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.bytes.as_ref().len() * Self::PER_BYTE
+    }
+
+    #[inline(always)]
+    fn get(&self, i: usize) -> C {
+        let (byte, shift) = Self::locate(i);
+        C::from_bits((self.bytes.as_ref()[byte] >> shift) & Self::MASK)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, i: usize, color: C) {
+        let (byte, shift) = Self::locate(i);
+        let b = &mut self.bytes.as_mut()[byte];
+        *b = (*b & !(Self::MASK << shift)) | ((color.to_bits() & Self::MASK) << shift);
+    }
+
+    fn fill(&mut self, range: Range<usize>, color: C) {
+        let (head, bytes, tail) = Self::split(range);
+        for i in head.chain(tail) {
+            self.set(i, color);
+        }
+        // Repeat the pixel's bits across the byte, e.g. 0b10 -> 0b1010_1010.
+        let pattern = (color.to_bits() & Self::MASK) * (0xFF / Self::MASK);
+        self.bytes.as_mut()[bytes].fill(pattern);
+    }
+
+    fn copy_from(&mut self, src: &Self, range: Range<usize>) {
+        let (head, bytes, tail) = Self::split(range);
+        for i in head.chain(tail) {
+            self.set(i, src.get(i));
+        }
+        self.bytes.as_mut()[bytes.clone()].copy_from_slice(&src.bytes.as_ref()[bytes]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Debug;
+    use embedded_graphics_core::pixelcolor::{BinaryColor, Gray2, Gray4, Gray8};
+    use embedded_graphics_core::primitives::Rectangle;
+
+    /// 7x8 pixels: rows of 7 never start on a byte boundary below 8 bpp.
+    const W: u32 = 7;
+    const H: u32 = 8;
+    const N: usize = 56;
+
+    type PackedFb<C, const BYTES: usize> = Framebuffer<C, N, Packed<C, [u8; BYTES]>>;
+
+    /// Color number `i` of the depth, cycling through every value it has.
+    fn color<C: PackedColor>(i: usize) -> C {
+        C::from_bits((i * 5 + 3) as u8 & ((1u16 << C::BITS) - 1) as u8)
+    }
+
+    /// Fills starting and ending at every offset within a byte, across row ends.
+    fn draw<C: PackedColor>(target: &mut impl DrawTarget<Color = C>, frame: usize) {
+        for i in 0..12 {
+            let k = i + frame * 12;
+            let area = Rectangle::new(
+                Point::new((k % 7) as i32 - 1, (k * 3 % 9) as i32 - 1),
+                Size::new((k * 5 % 8) as u32 + 1, (k % 4) as u32 + 1),
+            );
+            let _ = target.fill_solid(&area, color(k));
+        }
+        let area = Rectangle::new(Point::new(frame as i32 + 1, 2), Size::new(5, 3));
+        let _ = target.fill_contiguous(&area, (0..15).map(|i| color(i + frame)));
+    }
+
+    fn assert_same<C, B>(packed: &Framebuffer<C, N, B>, plain: &Framebuffer<C, N>)
+    where
+        C: PackedColor + Debug,
+        B: PixelStorage<C>,
+    {
+        for p in plain.bounding_box().points() {
+            assert_eq!(packed.get_pixel(p), plain.get_pixel(p), "at {p:?}");
+        }
+    }
+
+    fn fills_match_plain<C, const BYTES: usize>()
+    where
+        C: PackedColor + Debug,
+    {
+        let mut packed = PackedFb::<C, BYTES>::packed(W, H);
+        let mut plain = Framebuffer::<C, N>::filled(C::from_bits(0), W, H);
+        for frame in 0..4 {
+            draw(&mut packed, frame);
+            draw(&mut plain, frame);
+            assert_same(&packed, &plain);
+        }
+    }
+
+    fn copies_match_plain<C, const BYTES: usize>()
+    where
+        C: PackedColor + Debug,
+    {
+        let mut src = Packed::<C, _>::new([0u8; BYTES]);
+        let mut plain_src = [C::from_bits(0); N];
+        for i in 0..N {
+            src.set(i, color(i));
+            plain_src.set(i, color(i));
+        }
+        for range in [3..29, 9..10, 8..16, 15..17, 0..N, 1..N - 1] {
+            let mut dst = Packed::<C, _>::new([0u8; BYTES]);
+            let mut plain_dst = [C::from_bits(0); N];
+            dst.copy_from(&src, range.clone());
+            plain_dst.copy_from(&plain_src, range.clone());
+            assert!(dst.pixels(0..N).eq(plain_dst), "range {range:?}");
+        }
+    }
+
+    fn double_buffer_matches_plain<C, const BYTES: usize>()
+    where
+        C: PackedColor + Debug,
+    {
+        let mut db = DoubleBuffer::from_framebuffers(
+            PackedFb::<C, BYTES>::packed(W, H),
+            PackedFb::<C, BYTES>::packed(W, H),
+        );
+        let mut display = Framebuffer::<C, N>::filled(color(1), W, H);
+        let mut expected = Framebuffer::<C, N>::filled(C::from_bits(0), W, H);
+        for frame in 0..4 {
+            draw(&mut db, frame);
+            draw(&mut expected, frame);
+            db.flush(&mut display).unwrap();
+            assert_same(&display, &expected);
+        }
+    }
+
+    #[test]
+    fn packed_fills_match_plain_storage() {
+        fills_match_plain::<BinaryColor, 7>();
+        fills_match_plain::<Gray2, 14>();
+        fills_match_plain::<Gray4, 28>();
+        fills_match_plain::<Gray8, 56>();
+    }
+
+    #[test]
+    fn packed_copies_match_plain_storage() {
+        copies_match_plain::<BinaryColor, 7>();
+        copies_match_plain::<Gray2, 14>();
+        copies_match_plain::<Gray4, 28>();
+        copies_match_plain::<Gray8, 56>();
+    }
+
+    #[test]
+    fn packed_double_buffer_diffs() {
+        double_buffer_matches_plain::<BinaryColor, 7>();
+        double_buffer_matches_plain::<Gray2, 14>();
+        double_buffer_matches_plain::<Gray4, 28>();
+    }
+
+    #[test]
+    fn leftmost_pixel_in_most_significant_bits() {
+        let mut mono = Framebuffer::<BinaryColor, 16, Packed<_, [u8; 2]>>::packed(16, 1);
+        mono.set_pixel(Point::new(0, 0), BinaryColor::On);
+        mono.set_pixel(Point::new(9, 0), BinaryColor::On);
+        assert_eq!(mono.storage().as_bytes(), &[0x80, 0x40]);
+
+        let mut gray2 = Framebuffer::<Gray2, 4, Packed<_, [u8; 1]>>::packed(4, 1);
+        gray2.set_pixel(Point::new(0, 0), Gray2::new(3));
+        gray2.set_pixel(Point::new(3, 0), Gray2::new(1));
+        assert_eq!(gray2.storage().as_bytes(), &[0xC1]);
+
+        let mut gray4 = Framebuffer::<Gray4, 4, Packed<_, [u8; 2]>>::packed(4, 1);
+        gray4.set_pixel(Point::new(0, 0), Gray4::new(0xA));
+        gray4.set_pixel(Point::new(3, 0), Gray4::new(0x5));
+        assert_eq!(gray4.storage().as_bytes(), &[0xA0, 0x05]);
+    }
+}
@@ -0,0 +1,277 @@
+use core::marker::PhantomData;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+use crate::*;
+
+/// Raw panel transport: an address window followed by the pixel bytes that fill it.
+pub trait PanelSink {
+    type Error;
+
+    /// Set the address window; subsequent writes fill it row-major.
+    fn set_window(&mut self, area: &Rectangle) -> Result<(), Self::Error>;
+
+    /// Write wire-format pixel bytes into the current window.
+    fn write_pixels(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Bytes staged before each [`PanelSink::write_pixels`] call.
+const CHUNK: usize = 256;
+
+/// Drives a [`PanelSink`] as a `DrawTarget` of wire colors, so a [`Canvas`] can flush to it.
+///
+/// Every flushed rectangle becomes one `set_window` followed by its pixels, so dirty areas are sent
+/// with a window each.
+pub struct Panel<P, C> {
+    sink: P,
+    size: Size,
+    _color: PhantomData<C>,
+}
+
+impl<P, C> Panel<P, C>
+where
+    P: PanelSink,
+    C: WireColor,
+{
+    pub fn new(sink: P, size: Size) -> Self {
+        Self {
+            sink,
+            size,
+            _color: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.sink
+    }
+
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.sink
+    }
+
+    pub fn into_inner(self) -> P {
+        self.sink
+    }
+
+    /// Stream `colors` into the current window in chunks.
+    fn write<I>(&mut self, colors: I) -> Result<(), P::Error>
+    where
+        I: IntoIterator<Item = C>,
+    {
+        let mut chunk = [0u8; CHUNK];
+        let mut len = 0;
+        for c in colors {
+            if len + C::BYTES > CHUNK {
+                self.sink.write_pixels(&chunk[..len])?;
+                len = 0;
+            }
+            chunk[len..len + C::BYTES].copy_from_slice(c.as_bytes());
+            len += C::BYTES;
+        }
+        if len > 0 {
+            self.sink.write_pixels(&chunk[..len])?;
+        }
+        Ok(())
+    }
+}
+
+impl<P, C> OriginDimensions for Panel<P, C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<P, C> DrawTarget for Panel<P, C>
+where
+    P: PanelSink,
+    C: WireColor,
+{
+    type Color = C;
+    type Error = P::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        for Pixel(p, c) in pixels {
+            if bounds.contains(p) {
+                self.sink.set_window(&Rectangle::new(p, Size::new(1, 1)))?;
+                self.sink.write_pixels(c.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clipped = area.intersection(&self.bounding_box());
+        if clipped.is_zero_sized() {
+            return Ok(());
+        }
+        self.sink.set_window(&clipped)?;
+        if clipped == *area {
+            return self.write(colors);
+        }
+        let inside = area
+            .points()
+            .zip(colors)
+            .filter(|(p, _)| clipped.contains(*p))
+            .map(|(_, c)| c);
+        self.write(inside)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        self.sink.set_window(&area)?;
+        let count = area.size.width as usize * area.size.height as usize;
+        self.write(core::iter::repeat_n(color, count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{RecordingSink, SinkOp};
+
+    type Sink = RecordingSink<8, 256>;
+    type Recorder<'a, S> = Canvas<'a, Panel<Sink, WireRgb565>, S>;
+
+    fn window(x: i32, y: i32, width: u32, height: u32) -> SinkOp {
+        SinkOp::Window(Rectangle::new(Point::new(x, y), Size::new(width, height)))
+    }
+
+    /// Send a blue frame, then forget what was recorded.
+    fn clear_blue<S>(canvas: &mut Recorder<'_, S>)
+    where
+        S: BufferStrategy<Color = WireRgb565>,
+    {
+        canvas.clear(WireRgb565::BLUE).ok();
+        canvas.flush().unwrap();
+        canvas.target_mut().inner_mut().clear();
+    }
+
+    fn sink<'c, S>(canvas: &'c Recorder<'_, S>) -> &'c Sink
+    where
+        S: BufferStrategy<Color = WireRgb565>,
+    {
+        canvas.target().inner()
+    }
+
+    /// Whether the recorded pixel bytes are `colors`, in order.
+    fn sent(sink: &Sink, colors: &[WireRgb565]) -> bool {
+        sink.bytes()
+            .chunks_exact(WireRgb565::BYTES)
+            .eq(colors.iter().map(|c| c.as_bytes()))
+    }
+
+    #[test]
+    fn dirty_areas_get_a_window_each() {
+        let mut panel = Panel::new(Sink::new(), Size::new(8, 6));
+        let mut canvas = Canvas::<_, SingleBuffer<WireRgb565, 48>>::single_buffered(&mut panel);
+        canvas.clear(WireRgb565::BLUE).unwrap();
+        canvas.flush().unwrap();
+        assert_eq!(
+            sink(&canvas).ops(),
+            &[window(0, 0, 8, 6), SinkOp::Pixels(96)]
+        );
+        canvas.target_mut().inner_mut().clear();
+
+        let red = Rectangle::new(Point::new(1, 1), Size::new(2, 2));
+        let green = Rectangle::new(Point::new(6, 3), Size::new(1, 2));
+        canvas.fill_solid(&red, WireRgb565::RED).unwrap();
+        canvas.fill_solid(&green, WireRgb565::GREEN).unwrap();
+        canvas.flush().unwrap();
+        assert_eq!(
+            sink(&canvas).ops(),
+            &[
+                window(1, 1, 2, 2),
+                SinkOp::Pixels(8),
+                window(6, 3, 1, 2),
+                SinkOp::Pixels(4),
+            ]
+        );
+        let mut expected = [WireRgb565::RED; 6];
+        expected[4..].fill(WireRgb565::GREEN);
+        assert!(sent(sink(&canvas), &expected));
+    }
+
+    #[test]
+    fn diff_sends_only_changed_spans() {
+        let mut panel = Panel::new(Sink::new(), Size::new(8, 6));
+        let mut canvas = Canvas::<_, DoubleBuffer<WireRgb565, 48>>::double_buffered(&mut panel);
+        clear_blue(&mut canvas);
+
+        // Repainting blue changes nothing but the two red pixels, which are close enough on
+        // their row to share a window.
+        canvas.clear(WireRgb565::BLUE).unwrap();
+        Pixel(Point::new(1, 2), WireRgb565::RED)
+            .draw(&mut canvas)
+            .unwrap();
+        Pixel(Point::new(4, 2), WireRgb565::RED)
+            .draw(&mut canvas)
+            .unwrap();
+        canvas.flush().unwrap();
+        assert_eq!(
+            sink(&canvas).ops(),
+            &[window(1, 2, 4, 1), SinkOp::Pixels(8)]
+        );
+        let (r, b) = (WireRgb565::RED, WireRgb565::BLUE);
+        assert!(sent(sink(&canvas), &[r, b, b, r]));
+
+        // Without diffing the whole repainted area goes out.
+        canvas.target_mut().inner_mut().clear();
+        canvas.strategy_mut().set_diff_policy(DiffPolicy::Off);
+        canvas.clear(WireRgb565::BLUE).unwrap();
+        canvas.flush().unwrap();
+        assert_eq!(
+            sink(&canvas).ops(),
+            &[window(0, 0, 8, 6), SinkOp::Pixels(96)]
+        );
+    }
+
+    #[test]
+    fn scaled_pixels_become_blocks() {
+        let mut panel = Panel::new(Sink::new(), Size::new(8, 6));
+        let strategy = SingleBuffer::<WireRgb565, 12>::new(4, 3);
+        let mut canvas = Canvas::with_strategy(&mut panel, strategy).with_scale(2);
+        clear_blue(&mut canvas);
+
+        let area = Rectangle::new(Point::new(1, 1), Size::new(2, 1));
+        let colors = [WireRgb565::RED, WireRgb565::GREEN];
+        canvas.fill_contiguous(&area, colors).unwrap();
+        canvas.flush().unwrap();
+        assert_eq!(
+            sink(&canvas).ops(),
+            &[window(2, 2, 4, 2), SinkOp::Pixels(16)]
+        );
+        let row = [colors[0], colors[0], colors[1], colors[1]];
+        let block: [WireRgb565; 8] = core::array::from_fn(|i| row[i % 4]);
+        assert!(sent(sink(&canvas), &block));
+    }
+
+    #[test]
+    fn rotated_areas_land_turned() {
+        // A 4x3 image on a 3x4 panel turned clockwise: image (x, y) lands on panel (2 - y, x).
+        let mut panel = Panel::new(Sink::new(), Size::new(3, 4));
+        let strategy = SingleBuffer::<WireRgb565, 12>::new(4, 3);
+        let mut canvas = Canvas::with_strategy(&mut panel, strategy)
+            .with_orientation(Orientation::rotated(Rotation::Deg90));
+        clear_blue(&mut canvas);
+
+        let area = Rectangle::new(Point::new(1, 0), Size::new(2, 1));
+        let colors = [WireRgb565::RED, WireRgb565::GREEN];
+        canvas.fill_contiguous(&area, colors).unwrap();
+        canvas.flush().unwrap();
+        assert_eq!(
+            sink(&canvas).ops(),
+            &[window(2, 1, 1, 2), SinkOp::Pixels(4)]
+        );
+        assert!(sent(sink(&canvas), &colors));
+    }
+}
@@ -3,17 +3,18 @@ use embedded_graphics_core::pixelcolor::*;
 
 /// Simple RGBA color wrapper.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Rgba<C: RgbColor>(C, u8);
+pub struct Rgba<C: PixelColor>(C, u8);
 
 #[inline(always)]
 fn mul_blend_u8(delta: u32, a: u32) -> u32 {
     // Exact (delta * a) / 255 using the div255 trick (no slow integer division).
     // Valid for 0..=65535 inputs; see Hacker's Delight 10-16.
-    let t = delta * a + 128;
-    (t + (t >> 8)) >> 8
+    // Negative deltas arrive wrapped; the wrapped result truncates to the right channel value.
+    let t = delta.wrapping_mul(a).wrapping_add(128);
+    t.wrapping_add(t >> 8) >> 8
 }
 
-impl<C: RgbColor> Rgba<C> {
+impl<C: PixelColor> Rgba<C> {
     /// Create a new RGBA color.
     pub const fn new(color: C, alpha: u8) -> Self {
         Self(color, alpha)
@@ -24,6 +25,13 @@ impl<C: RgbColor> Rgba<C> {
         self.0
     }
 
+    /// Get the alpha component (0..=255).
+    pub const fn a(&self) -> u8 {
+        self.1
+    }
+}
+
+impl<C: RgbColor> Rgba<C> {
     pub fn r(&self) -> u8 {
         self.0.r()
     }
@@ -35,14 +43,9 @@ impl<C: RgbColor> Rgba<C> {
     pub fn b(&self) -> u8 {
         self.0.b()
     }
-
-    /// Get the alpha component (0..=255).
-    pub const fn a(&self) -> u8 {
-        self.1
-    }
 }
 
-impl<C: RgbColor> PixelColor for Rgba<C> {
+impl<C: PixelColor> PixelColor for Rgba<C> {
     type Raw = C::Raw;
 }
 
@@ -73,9 +76,9 @@ impl Blend<Rgb565> for Rgba<Rgb565> {
         let bb = b & 0x1F;
 
         // Blend in native bit depth (5/6/5) using exact div-by-255 trick.
-        let r = (br + mul_blend_u8(fr.wrapping_sub(br), a)) & 0x1F;
-        let g = (bgc + mul_blend_u8(fg.wrapping_sub(bgc), a)) & 0x3F;
-        let bl = (bb + mul_blend_u8(fb.wrapping_sub(bb), a)) & 0x1F;
+        let r = br.wrapping_add(mul_blend_u8(fr.wrapping_sub(br), a)) & 0x1F;
+        let g = bgc.wrapping_add(mul_blend_u8(fg.wrapping_sub(bgc), a)) & 0x3F;
+        let bl = bb.wrapping_add(mul_blend_u8(fb.wrapping_sub(bb), a)) & 0x1F;
 
         let out = ((r << 11) | (g << 5) | bl) as u16;
         Rgb565::from(RawU16::new(out))
@@ -101,9 +104,9 @@ impl Blend<Rgb888> for Rgba<Rgb888> {
         let bgc = bg.g() as u32;
         let bb = bg.b() as u32;
 
-        let r = (br + mul_blend_u8(fr.wrapping_sub(br), a)) as u8;
-        let g = (bgc + mul_blend_u8(fg.wrapping_sub(bgc), a)) as u8;
-        let b = (bb + mul_blend_u8(fb.wrapping_sub(bb), a)) as u8;
+        let r = br.wrapping_add(mul_blend_u8(fr.wrapping_sub(br), a)) as u8;
+        let g = bgc.wrapping_add(mul_blend_u8(fg.wrapping_sub(bgc), a)) as u8;
+        let b = bb.wrapping_add(mul_blend_u8(fb.wrapping_sub(bb), a)) as u8;
 
         Rgb888::new(r, g, b)
     }
@@ -128,10 +131,52 @@ impl Blend<Rgb666> for Rgba<Rgb666> {
         let bgc = bg.g() as u32;
         let bb = bg.b() as u32;
 
-        let r = (br + mul_blend_u8(fr.wrapping_sub(br), a)) as u8; // 0..63
-        let g = (bgc + mul_blend_u8(fg.wrapping_sub(bgc), a)) as u8;
-        let b = (bb + mul_blend_u8(fb.wrapping_sub(bb), a)) as u8;
+        let r = br.wrapping_add(mul_blend_u8(fr.wrapping_sub(br), a)) as u8; // 0..63
+        let g = bgc.wrapping_add(mul_blend_u8(fg.wrapping_sub(bgc), a)) as u8;
+        let b = bb.wrapping_add(mul_blend_u8(fb.wrapping_sub(bb), a)) as u8;
 
         Rgb666::new(r, g, b)
     }
 }
+
+// Grayscale blends in luminance at the color's own depth, rounding to the nearest level.
+macro_rules! impl_gray_blend {
+    ($($color:ty),*) => {$(
+        impl Blend<$color> for Rgba<$color> {
+            #[inline(always)]
+            fn blend(&self, bg: $color) -> $color {
+                let a = self.a() as u32;
+                let f = self.rgb().luma() as u32;
+                let b = bg.luma() as u32;
+                // `new` masks the wrapped result back to the color's depth.
+                <$color>::new(b.wrapping_add(mul_blend_u8(f.wrapping_sub(b), a)) as u8)
+            }
+        }
+    )*};
+}
+
+impl_gray_blend!(Gray2, Gray4, Gray8);
+
+impl Blend<BinaryColor> for Rgba<BinaryColor> {
+    /// Blends in luminance (off = 0, on = 255) and quantizes at half brightness: the foreground
+    /// wins once it is at least half opaque.
+    #[inline(always)]
+    fn blend(&self, bg: BinaryColor) -> BinaryColor {
+        if self.a() >= 128 { self.rgb() } else { bg }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn darker_foreground_blends_down() {
+        let half = Rgba::new(Rgb565::BLACK, 128).blend(Rgb565::WHITE);
+        assert_eq!((half.r(), half.g(), half.b()), (15, 31, 15));
+        let half = Rgba::new(Rgb888::new(0, 100, 255), 128).blend(Rgb888::new(255, 200, 255));
+        assert_eq!((half.r(), half.g(), half.b()), (127, 150, 255));
+        let quarter = Rgba::new(Gray8::new(0), 64).blend(Gray8::new(200));
+        assert_eq!(quarter.luma(), 150);
+    }
+}
@@ -0,0 +1,132 @@
+use core::cell::{Cell, RefCell};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+/// One display shared by several canvases, each confined to a region of its own.
+///
+/// Regions are handed out by [`claim`](Self::claim) and never overlap; at most `M` are held at
+/// once. Access to the display is serialized through a `RefCell`, so regions belong to one thread,
+/// e.g. tasks on a single executor.
+pub struct SharedDisplay<T, const M: usize> {
+    target: RefCell<T>,
+    /// Claimed areas; zero-sized slots are free.
+    claims: Cell<[Rectangle; M]>,
+}
+
+impl<T, const M: usize> SharedDisplay<T, M>
+where
+    T: DrawTarget,
+{
+    pub fn new(target: T) -> Self {
+        Self {
+            target: RefCell::new(target),
+            claims: Cell::new([Rectangle::zero(); M]),
+        }
+    }
+
+    /// Reserve `area` of the display, clipped to its bounds. `None` when the area is empty,
+    /// overlaps another claimed region or all `M` slots are taken.
+    pub fn claim(&self, area: Rectangle) -> Option<Region<'_, T, M>> {
+        let area = area.intersection(&self.target.borrow().bounding_box());
+        if area.is_zero_sized() {
+            return None;
+        }
+        let mut claims = self.claims.get();
+        if claims
+            .iter()
+            .any(|c| !c.intersection(&area).is_zero_sized())
+        {
+            return None;
+        }
+        let slot = claims.iter().position(|c| c.is_zero_sized())?;
+        claims[slot] = area;
+        self.claims.set(claims);
+        Some(Region {
+            display: self,
+            area,
+            slot,
+        })
+    }
+
+    /// Give back the display; every region must have been dropped.
+    pub fn into_inner(self) -> T {
+        self.target.into_inner()
+    }
+}
+
+/// Part of a [`SharedDisplay`] claimed by one user, usually as the target of an
+/// [`OwnedCanvas`](crate::OwnedCanvas). Drawing outside the region is clipped; dropping it
+/// releases the area.
+pub struct Region<'d, T, const M: usize> {
+    display: &'d SharedDisplay<T, M>,
+    area: Rectangle,
+    slot: usize,
+}
+
+impl<T, const M: usize> Region<'_, T, M> {
+    /// The claimed area, in display coordinates.
+    #[inline]
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<T, const M: usize> Drop for Region<'_, T, M> {
+    fn drop(&mut self) {
+        let mut claims = self.display.claims.get();
+        claims[self.slot] = Rectangle::zero();
+        self.display.claims.set(claims);
+    }
+}
+
+impl<T, const M: usize> Dimensions for Region<'_, T, M> {
+    #[inline(always)]
+    fn bounding_box(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<T, const M: usize> DrawTarget for Region<'_, T, M>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+        let inside = pixels.into_iter().filter(|Pixel(p, _)| area.contains(*p));
+        self.display.target.borrow_mut().draw_iter(inside)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clipped = area.intersection(&self.area);
+        if clipped.is_zero_sized() {
+            return Ok(());
+        }
+        let mut target = self.display.target.borrow_mut();
+        if clipped == *area {
+            return target.fill_contiguous(area, colors);
+        }
+        let inside = area
+            .points()
+            .zip(colors)
+            .filter(|(p, _)| clipped.contains(*p))
+            .map(|(_, c)| c);
+        target.fill_contiguous(&clipped, inside)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let clipped = area.intersection(&self.area);
+        if clipped.is_zero_sized() {
+            return Ok(());
+        }
+        self.display.target.borrow_mut().fill_solid(&clipped, color)
+    }
+}
@@ -0,0 +1,105 @@
+use embedded_graphics_core::pixelcolor::raw::RawData;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+/// Work done for one frame, as returned by [`Canvas::flush`](crate::Canvas::flush).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Opaque pixels drawn into the canvas, after clipping.
+    pub pixels_written: u32,
+    /// Pixels blended through the canvas's [`AlphaCanvas`](crate::AlphaCanvas), after clipping.
+    pub pixels_blended: u32,
+    /// Rectangles handed to the display.
+    pub rects_flushed: u32,
+    /// Pixel data handed to the display, at the display color's bit depth.
+    pub bytes_sent: u32,
+}
+
+impl FrameStats {
+    /// Whether nothing was drawn.
+    #[inline]
+    pub fn is_unchanged(&self) -> bool {
+        self.pixels_written == 0 && self.pixels_blended == 0
+    }
+}
+
+/// Pixels of `area` inside `bounds`.
+#[inline]
+pub(crate) fn clipped_pixels(area: &Rectangle, bounds: &Rectangle) -> u32 {
+    let size = area.intersection(bounds).size;
+    size.width * size.height
+}
+
+/// Forwards to `target`, counting the rectangles and pixels that pass through.
+pub(crate) struct Counted<'a, T> {
+    target: &'a mut T,
+    rects: u32,
+    pixels: u32,
+}
+
+impl<'a, T> Counted<'a, T>
+where
+    T: DrawTarget,
+{
+    pub(crate) fn new(target: &'a mut T) -> Self {
+        Self {
+            target,
+            rects: 0,
+            pixels: 0,
+        }
+    }
+
+    /// Record the counts into `stats`.
+    pub(crate) fn finish(&self, stats: &mut FrameStats) {
+        let bits = self.pixels as u64 * <T::Color as PixelColor>::Raw::BITS_PER_PIXEL as u64;
+        stats.rects_flushed = self.rects;
+        stats.bytes_sent = bits.div_ceil(8) as u32;
+    }
+}
+
+impl<T> Dimensions for Counted<'_, T>
+where
+    T: DrawTarget,
+{
+    #[inline(always)]
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<T> DrawTarget for Counted<'_, T>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut count = 0;
+        let res = self
+            .target
+            .draw_iter(pixels.into_iter().inspect(|_| count += 1));
+        self.pixels += count;
+        res
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.rects += 1;
+        self.pixels += clipped_pixels(area, &self.target.bounding_box());
+        self.target.fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.rects += 1;
+        self.pixels += clipped_pixels(area, &self.target.bounding_box());
+        self.target.fill_solid(area, color)
+    }
+}
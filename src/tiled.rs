@@ -0,0 +1,65 @@
+use embedded_graphics_core::prelude::*;
+
+use crate::*;
+
+/// Renders a scene much larger than RAM through one small tile framebuffer.
+///
+/// The scene closure is replayed once per tile with the tile placed at its screen position, so
+/// drawing uses screen coordinates and anything outside the tile is clipped. Each finished tile is
+/// pushed to the target before the next one starts.
+pub struct TiledCanvas<'a, T, C, const N: usize>
+where
+    T: DrawTarget<Color = C>,
+    C: RgbColor,
+{
+    tile: Framebuffer<C, N>,
+    target: &'a mut T,
+}
+
+impl<'a, T, C, const N: usize> TiledCanvas<'a, T, C, N>
+where
+    T: DrawTarget<Color = C>,
+    C: RgbColor,
+{
+    /// Tiles are `tile_width` x `tile_height`; `N` must be their product.
+    pub fn new(target: &'a mut T, tile_width: u32, tile_height: u32) -> Self {
+        Self {
+            tile: Framebuffer::new(tile_width, tile_height),
+            target,
+        }
+    }
+
+    /// Render the scene tile by tile, row-major from the top-left.
+    ///
+    /// `draw` receives the tile framebuffer; wrap it in [`AlphaCanvas::new`] for blended drawing.
+    /// Tiles keep the previous tile's pixels, so `draw` should paint a background first.
+    pub fn render<F>(&mut self, mut draw: F) -> Result<(), T::Error>
+    where
+        F: FnMut(&mut Framebuffer<C, N>),
+    {
+        let screen = self.target.bounding_box();
+        let step = self.tile.size();
+
+        for y in screen.rows().step_by(step.height as usize) {
+            for x in screen.columns().step_by(step.width as usize) {
+                self.tile.set_origin(Point::new(x, y));
+                draw(&mut self.tile);
+
+                let area = self.tile.bounding_box().intersection(&screen);
+                self.target
+                    .fill_contiguous(&area, self.tile.iter_area(&area))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, C, const N: usize> OriginDimensions for TiledCanvas<'a, T, C, N>
+where
+    T: DrawTarget<Color = C> + OriginDimensions,
+    C: RgbColor,
+{
+    fn size(&self) -> Size {
+        self.target.size()
+    }
+}
@@ -0,0 +1,524 @@
+use core::convert::Infallible;
+use core::task::Poll;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+use crate::dirty::*;
+use crate::*;
+
+/// Non-blocking pixel transport, typically a DMA channel feeding the panel.
+///
+/// The transport may go on reading the pixels it was started with after `start` returns, so
+/// starting a transfer is `unsafe`: the caller vouches for the memory until it is done.
+pub trait Transfer<C> {
+    type Error;
+
+    /// Begin sending `pixels` (row-major) to `area` of the panel.
+    ///
+    /// # Safety
+    ///
+    /// `pixels` must stay valid and must not be written until [`poll`](Transfer::poll) reports
+    /// `Ready`, even if the borrow ends earlier.
+    unsafe fn start(&mut self, area: &Rectangle, pixels: &[C]) -> Result<(), Self::Error>;
+
+    /// Like [`start`](Transfer::start), for rows that begin `stride` pixels apart in `pixels`.
+    /// Called with the memory of framebuffers built with a stride. Returns how many rows, from
+    /// the top of `area`, were started (at least one); the caller starts the rest once
+    /// [`poll`](Transfer::poll) reports `Ready`. The default starts everything when the rows
+    /// are contiguous and one padded row otherwise; transports able to skip the padding (e.g.
+    /// 2D DMA) override it and start all rows at once.
+    ///
+    /// # Safety
+    ///
+    /// As for [`start`](Transfer::start).
+    unsafe fn start_strided(
+        &mut self,
+        area: &Rectangle,
+        pixels: &[C],
+        stride: u32,
+    ) -> Result<u32, Self::Error> {
+        let width = area.size.width;
+        if stride == width || area.size.height <= 1 {
+            // SAFETY: same memory, same contract.
+            unsafe { self.start(area, pixels) }?;
+            return Ok(area.size.height);
+        }
+        let row = Rectangle::new(area.top_left, Size::new(width, 1));
+        // SAFETY: the row lies within `pixels`, which the caller keeps in place.
+        unsafe { self.start(&row, &pixels[..width as usize]) }?;
+        Ok(1)
+    }
+
+    /// State of the transfer started last; `Ready` once it has finished or when idle.
+    fn poll(&mut self) -> Poll<Result<(), Self::Error>>;
+}
+
+/// Start sending `rows` of `frame` and return the rows `transfer` left for later.
+///
+/// # Safety
+///
+/// As for [`Transfer::start`], for the memory of `frame`.
+pub(crate) unsafe fn start_rows<C, const N: usize, B, X>(
+    transfer: &mut X,
+    frame: &Framebuffer<C, N, B>,
+    rows: &Rectangle,
+) -> Result<Rectangle, X::Error>
+where
+    C: RgbColor,
+    B: Storage<C>,
+    X: Transfer<C>,
+{
+    let pixels = frame.rows_memory(rows.top_left.y as u32, rows.size.height);
+    // SAFETY: forwarded to the caller.
+    let started = unsafe { transfer.start_strided(rows, pixels, frame.stride()) }?;
+    let started = started.clamp(1, rows.size.height);
+    Ok(Rectangle::new(
+        rows.top_left + Point::new(0, started as i32),
+        Size::new(rows.size.width, rows.size.height - started),
+    ))
+}
+
+/// Pixel memory that stays at the same address for the rest of the program, so a [`Transfer`] can
+/// keep reading it after the framebuffer that owns it is moved or dropped.
+///
+/// # Safety
+///
+/// Implementors must hand out the same memory, valid for `'static`, on every `as_ref`/`as_mut`.
+pub unsafe trait StableStorage<C>: Storage<C> {}
+
+// SAFETY: the exclusive `'static` borrow pins the memory and keeps everything else out of it.
+unsafe impl<C, const N: usize> StableStorage<C> for &'static mut [C; N] {}
+
+// SAFETY: as above.
+unsafe impl<C> StableStorage<C> for &'static mut [C] {}
+
+/// Ping-pong buffering: one framebuffer is handed to a [`Transfer`] while drawing continues into
+/// the other one.
+///
+/// [`start_flush`](Self::start_flush) sends the rows covering everything drawn since the previous
+/// flush as one contiguous slice, then switches buffers. The slice is raw framebuffer memory, so
+/// orientation and other flush stages of [`Output`] do not apply to it. The rows just sent are
+/// copied into the new back buffer, so drawing always continues from the latest frame.
+///
+/// Transfers need [`StableStorage`], e.g. framebuffers made by [`Framebuffer::init`] over
+/// `static` memory. Dropping the buffer while a transfer is in flight panics.
+pub struct PingPongBuffer<C, const N: usize, B = [C; N]>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    buffers: [Framebuffer<C, N, B>; 2],
+    back: usize,
+    in_flight: bool,
+    /// Rows of the frame in flight that the transfer has not been started on yet.
+    rest: Rectangle,
+}
+
+impl<C, const N: usize> PingPongBuffer<C, N>
+where
+    C: RgbColor,
+{
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::from_framebuffers([
+            Framebuffer::new(width, height),
+            Framebuffer::new(width, height),
+        ])
+    }
+}
+
+impl<C, const N: usize, B> PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Ping-pong buffer over two framebuffers of the same geometry; the first is drawn first.
+    pub fn from_framebuffers(mut buffers: [Framebuffer<C, N, B>; 2]) -> Self {
+        // The first flush sends the whole frame; after that the spare only receives copies.
+        buffers[1].take_dirty();
+        Self {
+            buffers,
+            back: 0,
+            in_flight: false,
+            rest: Rectangle::zero(),
+        }
+    }
+
+    /// Whether a transfer started by [`start_flush`](Self::start_flush) is still running.
+    #[inline]
+    pub fn is_flushing(&self) -> bool {
+        self.in_flight
+    }
+}
+
+impl<C, const N: usize, B> PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: StableStorage<C>,
+{
+    /// Drive the running transfer, starting the rows it has not been started on yet;
+    /// `Ready` once the whole frame has been sent or when nothing is in flight.
+    ///
+    /// On error, the rows not sent yet go out with the next flush.
+    pub fn poll_flush<X>(&mut self, transfer: &mut X) -> Poll<Result<(), X::Error>>
+    where
+        X: Transfer<C>,
+    {
+        if !self.in_flight {
+            return Poll::Ready(Ok(()));
+        }
+        let res = match transfer.poll() {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Ok(())) if !self.rest.is_zero_sized() => {
+                // SAFETY: as in `start_flush`; the front buffer is still the one in flight.
+                match unsafe { start_rows(transfer, &self.buffers[self.back ^ 1], &self.rest) } {
+                    Ok(rest) => {
+                        self.rest = rest;
+                        return Poll::Pending;
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            Poll::Ready(res) => res,
+        };
+        self.in_flight = false;
+        // The back buffer started from a copy of these rows, so it can send them instead.
+        let rest = core::mem::replace(&mut self.rest, Rectangle::zero());
+        if res.is_err() && !rest.is_zero_sized() {
+            self.buffers[self.back].mark_dirty(&rest);
+        }
+        Poll::Ready(res)
+    }
+
+    /// Hand the changed rows of the back buffer to `transfer` and switch buffers.
+    ///
+    /// Returns `Pending` without starting anything while the previous transfer is still running.
+    /// Transports that start padded rows one at a time get the rest from
+    /// [`poll_flush`](Self::poll_flush).
+    pub fn start_flush<X>(&mut self, transfer: &mut X) -> Poll<Result<(), X::Error>>
+    where
+        X: Transfer<C>,
+    {
+        match self.poll_flush(transfer) {
+            Poll::Ready(Ok(())) => {}
+            other => return other,
+        }
+
+        let dirty = self.buffers[self.back].take_dirty();
+        let band = dirty
+            .iter()
+            .fold(Rectangle::zero(), |acc, r| union(&acc, &r));
+        if band.is_zero_sized() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let front = &self.buffers[self.back];
+        let rows = Rectangle::new(
+            Point::new(0, band.top_left.y),
+            Size::new(front.width, band.size.height),
+        );
+        // SAFETY: the storage is stable and this buffer is not drawn until the transfer is
+        // polled to completion; dropping it while in flight panics.
+        match unsafe { start_rows(transfer, front, &rows) } {
+            Ok(rest) => self.rest = rest,
+            Err(e) => {
+                self.buffers[self.back].mark_dirty(&band);
+                return Poll::Ready(Err(e));
+            }
+        }
+        self.in_flight = true;
+
+        copy_between(&mut self.buffers, self.back ^ 1, self.back, &rows);
+        self.back ^= 1;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<C, const N: usize, B> Drop for PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn drop(&mut self) {
+        assert!(
+            !self.in_flight,
+            "PingPongBuffer dropped while a transfer is in flight"
+        );
+    }
+}
+
+impl<C, const N: usize, B> BufferStrategy for PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    /// Blocking flush of the back buffer through `out`, bypassing any [`Transfer`]. The areas
+    /// sent are copied into the spare buffer, so a later [`start_flush`](Self::start_flush)
+    /// carries on from them.
+    ///
+    /// Panics while a transfer is in flight: both would address the panel at once, so drive it
+    /// to completion with [`poll_flush`](Self::poll_flush) first.
+    fn flush_to<T>(&mut self, out: &mut Output<'_, T>) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = Self::Color>,
+    {
+        assert!(!self.in_flight, "flush_to while a transfer is in flight");
+        let back = self.back;
+        let dirty = self.buffers[back].take_dirty();
+        for (i, area) in dirty.iter().enumerate() {
+            if let Err(e) = out.send(&self.buffers[back], &area) {
+                self.buffers[back].restore_dirty(dirty.iter().skip(i));
+                return Err(e);
+            }
+            copy_between(&mut self.buffers, back ^ 1, back, &area);
+        }
+        Ok(())
+    }
+}
+
+impl<C, const N: usize, B> DrawTarget for PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.buffers[self.back].draw_iter(pixels)
+    }
+
+    #[inline(always)]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.buffers[self.back].fill_contiguous(area, colors)
+    }
+
+    #[inline(always)]
+    fn fill_solid(&mut self, area: &Rectangle, color: C) -> Result<(), Self::Error> {
+        self.buffers[self.back].fill_solid(area, color)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self, color: C) -> Result<(), Self::Error> {
+        self.buffers[self.back].clear(color)
+    }
+}
+
+impl<C, const N: usize, B> OriginDimensions for PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    fn size(&self) -> Size {
+        self.buffers[self.back].size()
+    }
+}
+
+impl<C, const N: usize, B> HasFramebuffer<C, N> for PingPongBuffer<C, N, B>
+where
+    C: RgbColor,
+    B: Storage<C>,
+{
+    type Storage = B;
+
+    fn current_mut(&mut self) -> &mut Framebuffer<C, N, B> {
+        &mut self.buffers[self.back]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::MockTransfer;
+    use core::mem::{ManuallyDrop, MaybeUninit};
+    use embedded_graphics_core::pixelcolor::Rgb565;
+    use std::boxed::Box;
+
+    type Stable = &'static mut [Rgb565; 12];
+
+    /// 3x4 ping-pong buffer over leaked memory, standing in for `static`s.
+    fn ping_pong() -> PingPongBuffer<Rgb565, 12, Stable> {
+        let leak = || Framebuffer::init(Box::leak(Box::new(MaybeUninit::uninit())), 3, 4);
+        PingPongBuffer::from_framebuffers([leak(), leak()])
+    }
+
+    /// Transfer without stride support that records the rows it was started with and stays
+    /// busy for one poll after each.
+    struct RowTransfer {
+        rows: [(Rectangle, [Rgb565; 3]); 4],
+        started: usize,
+        busy: bool,
+    }
+
+    impl Transfer<Rgb565> for RowTransfer {
+        type Error = ();
+
+        unsafe fn start(&mut self, area: &Rectangle, pixels: &[Rgb565]) -> Result<(), ()> {
+            if self.busy || pixels.len() != 3 {
+                return Err(());
+            }
+            self.rows[self.started] = (*area, pixels.try_into().unwrap());
+            self.started += 1;
+            self.busy = true;
+            Ok(())
+        }
+
+        fn poll(&mut self) -> Poll<Result<(), ()>> {
+            if core::mem::take(&mut self.busy) {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+
+    fn row(y: i32) -> Rectangle {
+        Rectangle::new(Point::new(0, y), Size::new(3, 1))
+    }
+
+    #[test]
+    fn ping_pong_sends_changed_rows() {
+        let mut pp = ping_pong();
+        let mut transfer = MockTransfer::<Rgb565, 12>::new(Rgb565::BLACK, 2);
+
+        pp.clear(Rgb565::BLUE).unwrap();
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        assert_eq!(
+            transfer.area(),
+            Rectangle::new(Point::zero(), Size::new(3, 4))
+        );
+        assert_eq!(transfer.pixels(), &[Rgb565::BLUE; 12]);
+        assert!(pp.is_flushing());
+
+        // Drawing goes on in the other buffer, which starts from the frame just sent.
+        Pixel(Point::new(1, 2), Rgb565::RED).draw(&mut pp).unwrap();
+        assert_eq!(
+            pp.current_mut().get_pixel(Point::new(0, 0)),
+            Some(Rgb565::BLUE)
+        );
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Pending);
+        assert_eq!(pp.poll_flush(&mut transfer), Poll::Pending);
+        assert_eq!(pp.poll_flush(&mut transfer), Poll::Ready(Ok(())));
+        assert!(!pp.is_flushing());
+        assert_eq!(transfer.started(), 1);
+
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        assert_eq!(transfer.started(), 2);
+        assert_eq!(transfer.area(), row(2));
+        assert_eq!(
+            transfer.pixels(),
+            &[Rgb565::BLUE, Rgb565::RED, Rgb565::BLUE]
+        );
+        while pp.poll_flush(&mut transfer).is_pending() {}
+    }
+
+    #[test]
+    fn ping_pong_keeps_blocking_flushes() {
+        let mut pp = ping_pong();
+        let mut transfer = MockTransfer::<Rgb565, 12>::new(Rgb565::BLACK, 0);
+        let mut display = Framebuffer::<Rgb565, 12>::new(3, 4);
+
+        pp.clear(Rgb565::BLUE).unwrap();
+        pp.flush(&mut display).unwrap();
+        Pixel(Point::new(0, 1), Rgb565::RED).draw(&mut pp).unwrap();
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        assert_eq!(
+            transfer.pixels(),
+            &[Rgb565::RED, Rgb565::BLUE, Rgb565::BLUE]
+        );
+
+        // The buffer drawn now missed the blocking flush; its pixels must not come back black.
+        Pixel(Point::new(2, 3), Rgb565::GREEN)
+            .draw(&mut pp)
+            .unwrap();
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        assert_eq!(transfer.area(), row(3));
+        assert_eq!(
+            transfer.pixels(),
+            &[Rgb565::BLUE, Rgb565::BLUE, Rgb565::GREEN]
+        );
+        while pp.poll_flush(&mut transfer).is_pending() {}
+    }
+
+    #[test]
+    #[should_panic(expected = "flush_to while a transfer is in flight")]
+    fn ping_pong_flush_to_refuses_running_transfer() {
+        // Not dropped, so the assert under test is the only panic.
+        let mut pp = ManuallyDrop::new(ping_pong());
+        let mut transfer = MockTransfer::<Rgb565, 12>::new(Rgb565::BLACK, 1);
+        let mut display = Framebuffer::<Rgb565, 12>::new(3, 4);
+
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        let _ = pp.flush(&mut display);
+    }
+
+    #[test]
+    #[should_panic(expected = "dropped while a transfer is in flight")]
+    fn ping_pong_drop_refuses_running_transfer() {
+        let mut pp = ping_pong();
+        let mut transfer = MockTransfer::<Rgb565, 12>::new(Rgb565::BLACK, 1);
+
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        drop(pp);
+    }
+
+    #[test]
+    fn default_start_strided_starts_one_padded_row() {
+        let mut fb = Framebuffer::<Rgb565, 12>::with_stride(3, 3, 4);
+        fb.fill_solid(&row(0), Rgb565::RED).unwrap();
+        let mut transfer = RowTransfer {
+            rows: [(Rectangle::zero(), [Rgb565::BLACK; 3]); 4],
+            started: 0,
+            busy: false,
+        };
+        let area = Rectangle::new(Point::new(0, 0), Size::new(3, 3));
+        // SAFETY: `fb` outlives the transfer, which copies the rows right away.
+        let started =
+            unsafe { transfer.start_strided(&area, fb.rows_memory(0, 3), fb.stride()) }.unwrap();
+
+        assert_eq!(started, 1);
+        assert_eq!(transfer.started, 1);
+        assert!(transfer.busy, "row left in flight");
+        assert_eq!(transfer.rows[0], (row(0), [Rgb565::RED; 3]));
+    }
+
+    #[test]
+    fn ping_pong_starts_padded_rows_between_polls() {
+        let leak = || {
+            Framebuffer::from_storage_with_stride(Box::leak(Box::new([Rgb565::BLACK; 16])), 3, 4, 4)
+        };
+        let mut pp = PingPongBuffer::<Rgb565, 16, &'static mut [Rgb565; 16]>::from_framebuffers([
+            leak(),
+            leak(),
+        ]);
+        let mut transfer = RowTransfer {
+            rows: [(Rectangle::zero(), [Rgb565::BLACK; 3]); 4],
+            started: 0,
+            busy: false,
+        };
+
+        pp.clear(Rgb565::BLUE).unwrap();
+        assert_eq!(pp.start_flush(&mut transfer), Poll::Ready(Ok(())));
+        assert_eq!(transfer.started, 1);
+
+        // Each row takes one busy poll and one that starts the next row.
+        let mut polls = 0;
+        while pp.poll_flush(&mut transfer).is_pending() {
+            polls += 1;
+            assert!(pp.is_flushing());
+        }
+        assert_eq!(polls, 7);
+        assert!(!pp.is_flushing());
+        for (y, sent) in transfer.rows.iter().enumerate() {
+            assert_eq!(*sent, (row(y as i32), [Rgb565::BLUE; 3]));
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use embedded_graphics_core::prelude::*;
+
+use crate::*;
+
+/// Way the new frame moves onto the panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Effect used by [`DoubleBuffer::transition_to`] to go from the previous frame to the new one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// Blend the new frame over the old one with increasing opacity.
+    Crossfade,
+    /// Push the old frame out while the new one slides in behind it.
+    Slide(Direction),
+    /// Uncover the new frame behind an edge sweeping across the old one.
+    Wipe(Direction),
+    /// Uncover the new frame through a circle growing from the center.
+    Iris,
+}
+
+impl Transition {
+    /// Color at `p` of the frame `progress` (0..=255) of the way from `old` to `new`.
+    pub(crate) fn pixel<C, const N: usize, B>(
+        &self,
+        old: &Framebuffer<C, N, B>,
+        new: &Framebuffer<C, N, B>,
+        progress: u8,
+        p: Point,
+    ) -> C
+    where
+        C: PixelColor,
+        B: PixelStorage<C>,
+        Rgba<C>: Blend<C>,
+    {
+        let frame = new.bounding_box();
+        let at = |fb: &Framebuffer<C, N, B>, l: Point| fb.get_pixel(l + frame.top_left).unwrap();
+        let l = p - frame.top_left;
+        let (w, h) = (frame.size.width as i32, frame.size.height as i32);
+        let off = |len: i32| len * progress as i32 / 255;
+
+        match *self {
+            Transition::Crossfade => Rgba::new(at(new, l), progress).blend(at(old, l)),
+            Transition::Slide(dir) => {
+                let (x, y) = match dir {
+                    Direction::Left => (l.x + off(w), l.y),
+                    Direction::Right => (l.x - off(w), l.y),
+                    Direction::Up => (l.x, l.y + off(h)),
+                    Direction::Down => (l.x, l.y - off(h)),
+                };
+                if (0..w).contains(&x) && (0..h).contains(&y) {
+                    at(old, Point::new(x, y))
+                } else {
+                    at(new, Point::new(x.rem_euclid(w), y.rem_euclid(h)))
+                }
+            }
+            Transition::Wipe(dir) => {
+                let uncovered = match dir {
+                    Direction::Left => l.x >= w - off(w),
+                    Direction::Right => l.x < off(w),
+                    Direction::Up => l.y >= h - off(h),
+                    Direction::Down => l.y < off(h),
+                };
+                at(if uncovered { new } else { old }, l)
+            }
+            Transition::Iris => {
+                // Distances are doubled so the center falls on a whole number.
+                let dx = (2 * l.x + 1 - w) as i64;
+                let dy = (2 * l.y + 1 - h) as i64;
+                let full = (w as i64).pow(2) + (h as i64).pow(2);
+                let radius = full * (progress as i64).pow(2) / (255 * 255);
+                let inside = dx * dx + dy * dy < radius;
+                at(if inside { new } else { old }, l)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::{Gray8, GrayColor, Rgb565};
+
+    /// 8x4 frames whose luma encodes the position: `10 * x + y`, plus 100 in the new frame.
+    fn frames() -> (Framebuffer<Gray8, 32>, Framebuffer<Gray8, 32>) {
+        let mut old = Framebuffer::filled(Gray8::BLACK, 8, 4);
+        let mut new = Framebuffer::filled(Gray8::BLACK, 8, 4);
+        for p in old.bounding_box().points() {
+            let luma = (10 * p.x + p.y) as u8;
+            old.set_pixel(p, Gray8::new(luma));
+            new.set_pixel(p, Gray8::new(100 + luma));
+        }
+        (old, new)
+    }
+
+    fn luma(transition: Transition, progress: u8, x: i32, y: i32) -> u8 {
+        let (old, new) = frames();
+        transition
+            .pixel(&old, &new, progress, Point::new(x, y))
+            .luma()
+    }
+
+    const ALL: [Transition; 10] = [
+        Transition::Crossfade,
+        Transition::Slide(Direction::Left),
+        Transition::Slide(Direction::Right),
+        Transition::Slide(Direction::Up),
+        Transition::Slide(Direction::Down),
+        Transition::Wipe(Direction::Left),
+        Transition::Wipe(Direction::Right),
+        Transition::Wipe(Direction::Up),
+        Transition::Wipe(Direction::Down),
+        Transition::Iris,
+    ];
+
+    #[test]
+    fn every_effect_starts_old_and_ends_new() {
+        let (old, new) = frames();
+        for transition in ALL {
+            for p in new.bounding_box().points() {
+                let at = |progress| transition.pixel(&old, &new, progress, p);
+                assert_eq!(at(0), old.get_pixel(p).unwrap(), "{transition:?} at {p:?}");
+                assert_eq!(
+                    at(255),
+                    new.get_pixel(p).unwrap(),
+                    "{transition:?} at {p:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn crossfade_blends_halfway() {
+        assert_eq!(luma(Transition::Crossfade, 0, 0, 0), 0);
+        assert_eq!(luma(Transition::Crossfade, 128, 0, 0), 50);
+        assert_eq!(luma(Transition::Crossfade, 255, 0, 0), 100);
+    }
+
+    #[test]
+    fn slide_shifts_both_frames() {
+        // Halfway across 8 columns: the old frame moved 4 columns left, the new one follows.
+        let left = Transition::Slide(Direction::Left);
+        assert_eq!(luma(left, 0, 1, 1), 11);
+        assert_eq!(luma(left, 128, 1, 1), 51);
+        assert_eq!(luma(left, 128, 5, 1), 111);
+        assert_eq!(luma(left, 255, 5, 1), 151);
+
+        let down = Transition::Slide(Direction::Down);
+        assert_eq!(luma(down, 128, 1, 3), 11);
+        assert_eq!(luma(down, 128, 1, 1), 113);
+    }
+
+    #[test]
+    fn wipe_uncovers_from_the_edge() {
+        let right = Transition::Wipe(Direction::Right);
+        assert_eq!(luma(right, 0, 3, 0), 30);
+        assert_eq!(luma(right, 128, 3, 0), 130);
+        assert_eq!(luma(right, 128, 4, 0), 40);
+        assert_eq!(luma(right, 255, 4, 0), 140);
+
+        let up = Transition::Wipe(Direction::Up);
+        assert_eq!(luma(up, 128, 0, 2), 102);
+        assert_eq!(luma(up, 128, 0, 1), 1);
+    }
+
+    #[test]
+    fn iris_grows_from_the_center() {
+        assert_eq!(luma(Transition::Iris, 0, 3, 1), 31);
+        assert_eq!(luma(Transition::Iris, 128, 3, 1), 131);
+        assert_eq!(luma(Transition::Iris, 128, 0, 0), 0);
+        assert_eq!(luma(Transition::Iris, 255, 0, 0), 100);
+    }
+
+    #[test]
+    fn last_step_makes_the_new_frame_the_reference() {
+        let mut display = Framebuffer::<Rgb565, 32>::new(8, 4);
+        let mut db = DoubleBuffer::<Rgb565, 32>::new(8, 4);
+        let frame = db.bounding_box();
+        db.clear(Rgb565::BLUE).unwrap();
+        db.flush(&mut display).unwrap();
+
+        db.clear(Rgb565::RED).unwrap();
+        let mut out = Output::new(&mut display, frame);
+        db.transition_to(&mut out, Transition::Crossfade, 128)
+            .unwrap();
+        assert!(!db.current_mut().dirty().is_empty());
+        db.transition_to(&mut out, Transition::Crossfade, 255)
+            .unwrap();
+        assert!(db.current_mut().dirty().is_empty());
+        assert!(display.as_slice().iter().all(|&c| c == Rgb565::RED));
+
+        // Redrawing what the panel already shows sends nothing; only the real change goes out.
+        display.clear(Rgb565::GREEN).unwrap();
+        Pixel(Point::new(0, 0), Rgb565::RED).draw(&mut db).unwrap();
+        Pixel(Point::new(1, 0), Rgb565::BLUE).draw(&mut db).unwrap();
+        db.flush(&mut display).unwrap();
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb565::GREEN));
+        assert_eq!(display.get_pixel(Point::new(1, 0)), Some(Rgb565::BLUE));
+    }
+}
@@ -0,0 +1,182 @@
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::*;
+
+use crate::*;
+
+/// Mutable window onto part of a [`Framebuffer`], created by [`Framebuffer::view`].
+///
+/// Coordinates are local to the window, drawing is clipped to it, and every write marks the
+/// touched area of the framebuffer dirty.
+pub struct FramebufferView<'a, C: PixelColor, const N: usize, B = [C; N]> {
+    fb: &'a mut Framebuffer<C, N, B>,
+    /// The window in the framebuffer's draw coordinates.
+    area: Rectangle,
+}
+
+impl<'a, C: PixelColor, const N: usize, B> FramebufferView<'a, C, N, B>
+where
+    B: PixelStorage<C>,
+{
+    pub(crate) fn new(fb: &'a mut Framebuffer<C, N, B>, area: &Rectangle) -> Self {
+        let area = area.intersection(&fb.bounding_box());
+        Self { fb, area }
+    }
+
+    /// The window in the framebuffer's draw coordinates.
+    #[inline]
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Color at `p` (local to the window), `None` outside it.
+    #[inline]
+    pub fn get_pixel(&self, p: Point) -> Option<C> {
+        if self.contains(p) {
+            self.fb.get_pixel(p + self.area.top_left)
+        } else {
+            None
+        }
+    }
+
+    /// Set the color at `p` (local to the window); points outside are ignored.
+    #[inline]
+    pub fn set_pixel(&mut self, p: Point, color: C) {
+        if self.contains(p) {
+            self.fb.set_pixel(p + self.area.top_left, color);
+        }
+    }
+
+    #[inline]
+    fn contains(&self, p: Point) -> bool {
+        Rectangle::new(Point::zero(), self.area.size).contains(p)
+    }
+
+    /// Column and row of the window's top-left pixel within the framebuffer.
+    #[inline]
+    fn offset(&self) -> (usize, u32) {
+        let local = self.area.top_left - self.fb.origin();
+        (local.x as usize, local.y as u32)
+    }
+}
+
+impl<C: PixelColor, const N: usize, B> FramebufferView<'_, C, N, B>
+where
+    B: Storage<C>,
+{
+    /// Mutable pixels of row `y` of the window; that row of the window is marked dirty. Panics
+    /// unless `y` is inside the window.
+    pub fn row_mut(&mut self, y: u32) -> &mut [C] {
+        assert!(y < self.area.size.height, "row outside the view");
+        let (x0, y0) = self.offset();
+        let w = self.area.size.width;
+        let row = Rectangle::new(
+            self.area.top_left + Point::new(0, y as i32),
+            Size::new(w, 1),
+        );
+        self.fb.mark_dirty(&row);
+        let start = (y0 + y) as usize * self.fb.stride() as usize + x0;
+        &mut self.fb.buf_mut()[start..start + w as usize]
+    }
+
+    /// Mutable rows of the window from top to bottom; the window is marked dirty.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [C]> + '_ {
+        let (x0, y0) = self.offset();
+        let w = self.area.size.width as usize;
+        let stride = self.fb.stride() as usize;
+        self.fb.mark_dirty(&self.area);
+        self.fb
+            .buf_mut()
+            .chunks_mut(stride)
+            .skip(y0 as usize)
+            .take(self.area.size.height as usize)
+            .map(move |row| &mut row[x0..x0 + w])
+    }
+}
+
+impl<C: PixelColor, const N: usize, B> OriginDimensions for FramebufferView<'_, C, N, B> {
+    #[inline(always)]
+    fn size(&self) -> Size {
+        self.area.size
+    }
+}
+
+impl<C: PixelColor, const N: usize, B> DrawTarget for FramebufferView<'_, C, N, B>
+where
+    B: PixelStorage<C>,
+{
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+        let pixels = pixels
+            .into_iter()
+            .map(|Pixel(p, c)| Pixel(p + area.top_left, c))
+            .filter(|Pixel(p, _)| area.contains(*p));
+        self.fb.draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let target = Rectangle::new(area.top_left + self.area.top_left, area.size);
+        let clipped = target.intersection(&self.area);
+        if clipped == target {
+            return self.fb.fill_contiguous(&target, colors);
+        }
+        let inside = target
+            .points()
+            .zip(colors)
+            .filter(|(p, _)| clipped.contains(*p))
+            .map(|(_, c)| c);
+        self.fb.fill_contiguous(&clipped, inside)
+    }
+
+    #[inline]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let target = Rectangle::new(area.top_left + self.area.top_left, area.size);
+        self.fb.fill_solid(&target.intersection(&self.area), color)
+    }
+
+    #[inline]
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fb.fill_solid(&self.area, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    #[test]
+    fn view_row_stays_inside_window() {
+        let mut fb = Framebuffer::<Rgb565, 20>::new(4, 5);
+        fb.take_dirty();
+        let area = Rectangle::new(Point::new(1, 1), Size::new(2, 3));
+        fb.view(&area).row_mut(1).fill(Rgb565::RED);
+
+        let row = Rectangle::new(Point::new(1, 2), Size::new(2, 1));
+        assert!(fb.dirty().iter().eq([row]));
+        for p in fb.bounding_box().points() {
+            let color = if row.contains(p) {
+                Rgb565::RED
+            } else {
+                Rgb565::BLACK
+            };
+            assert_eq!(fb.get_pixel(p), Some(color), "at {p:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "row outside the view")]
+    fn view_row_below_window() {
+        let mut fb = Framebuffer::<Rgb565, 20>::new(4, 5);
+        fb.view(&Rectangle::new(Point::new(1, 1), Size::new(2, 3)))
+            .row_mut(3);
+    }
+}
@@ -0,0 +1,181 @@
+use embedded_graphics_core::pixelcolor::raw::*;
+use embedded_graphics_core::pixelcolor::*;
+
+use crate::*;
+
+/// Colors laid out in memory exactly as a panel expects them on the wire, so a framebuffer of them
+/// can be sent as raw bytes with [`Framebuffer::as_bytes`].
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(transparent)]` wrappers around `[u8; BYTES]` for which every byte
+/// pattern is a valid value.
+pub unsafe trait WireColor: RgbColor {
+    /// Bytes per pixel on the wire.
+    const BYTES: usize;
+
+    /// The pixel as sent to the panel.
+    #[inline(always)]
+    fn as_bytes(&self) -> &[u8] {
+        // SAFETY: implementors are transparent byte arrays of `BYTES` bytes.
+        unsafe { core::slice::from_raw_parts((self as *const Self).cast(), Self::BYTES) }
+    }
+}
+
+/// RGB565 stored big-endian, as SPI panels receive it.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WireRgb565([u8; 2]);
+
+impl WireRgb565 {
+    /// Create a color from 5/6/5-bit channels.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        let v = ((r as u16 & 0x1F) << 11) | ((g as u16 & 0x3F) << 5) | (b as u16 & 0x1F);
+        Self(v.to_be_bytes())
+    }
+
+    #[inline(always)]
+    const fn bits(&self) -> u16 {
+        u16::from_be_bytes(self.0)
+    }
+}
+
+/// RGB666 as three bytes with each channel in the upper six bits, as 18-bit SPI panels receive it.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WireRgb666([u8; 3]);
+
+impl WireRgb666 {
+    /// Create a color from 6-bit channels.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self([(r & 0x3F) << 2, (g & 0x3F) << 2, (b & 0x3F) << 2])
+    }
+}
+
+/// RGB888 as three bytes in R, G, B order.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WireRgb888([u8; 3]);
+
+impl WireRgb888 {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self([r, g, b])
+    }
+}
+
+macro_rules! impl_wire_color {
+    ($wire:ident, $native:ident, $raw:ident, $bytes:expr, $r:expr, $g:expr, $b:expr) => {
+        impl PixelColor for $wire {
+            type Raw = $raw;
+        }
+
+        impl RgbColor for $wire {
+            #[inline(always)]
+            fn r(&self) -> u8 {
+                let f: fn(&Self) -> u8 = $r;
+                f(self)
+            }
+
+            #[inline(always)]
+            fn g(&self) -> u8 {
+                let f: fn(&Self) -> u8 = $g;
+                f(self)
+            }
+
+            #[inline(always)]
+            fn b(&self) -> u8 {
+                let f: fn(&Self) -> u8 = $b;
+                f(self)
+            }
+
+            const MAX_R: u8 = $native::MAX_R;
+            const MAX_G: u8 = $native::MAX_G;
+            const MAX_B: u8 = $native::MAX_B;
+
+            const BLACK: Self = Self::new(0, 0, 0);
+            const RED: Self = Self::new(Self::MAX_R, 0, 0);
+            const GREEN: Self = Self::new(0, Self::MAX_G, 0);
+            const BLUE: Self = Self::new(0, 0, Self::MAX_B);
+            const YELLOW: Self = Self::new(Self::MAX_R, Self::MAX_G, 0);
+            const MAGENTA: Self = Self::new(Self::MAX_R, 0, Self::MAX_B);
+            const CYAN: Self = Self::new(0, Self::MAX_G, Self::MAX_B);
+            const WHITE: Self = Self::new(Self::MAX_R, Self::MAX_G, Self::MAX_B);
+        }
+
+        // SAFETY: `#[repr(transparent)]` over `[u8; $bytes]`; channel getters mask unused bits.
+        unsafe impl WireColor for $wire {
+            const BYTES: usize = $bytes;
+        }
+
+        impl FromChannels for $wire {
+            #[inline(always)]
+            fn from_channels(r: u8, g: u8, b: u8) -> Self {
+                Self::new(r, g, b)
+            }
+        }
+
+        impl From<$native> for $wire {
+            #[inline(always)]
+            fn from(c: $native) -> Self {
+                Self::new(c.r(), c.g(), c.b())
+            }
+        }
+
+        impl From<$wire> for $native {
+            #[inline(always)]
+            fn from(c: $wire) -> Self {
+                $native::new(c.r(), c.g(), c.b())
+            }
+        }
+
+        impl Default for $wire {
+            fn default() -> Self {
+                Self::BLACK
+            }
+        }
+
+        impl Blend<$wire> for Rgba<$wire> {
+            #[inline(always)]
+            fn blend(&self, bg: $wire) -> $wire {
+                Rgba::new($native::from(self.rgb()), self.a())
+                    .blend($native::from(bg))
+                    .into()
+            }
+        }
+    };
+}
+
+impl_wire_color!(
+    WireRgb565,
+    Rgb565,
+    RawU16,
+    2,
+    |c| (c.bits() >> 11) as u8 & 0x1F,
+    |c| (c.bits() >> 5) as u8 & 0x3F,
+    |c| c.bits() as u8 & 0x1F
+);
+impl_wire_color!(
+    WireRgb666,
+    Rgb666,
+    RawU24,
+    3,
+    |c| c.0[0] >> 2,
+    |c| c.0[1] >> 2,
+    |c| c.0[2] >> 2
+);
+impl_wire_color!(WireRgb888, Rgb888, RawU24, 3, |c| c.0[0], |c| c.0[1], |c| c
+    .0[2]);
+
+impl From<Rgb888> for WireRgb565 {
+    #[inline(always)]
+    fn from(c: Rgb888) -> Self {
+        Rgb565::from(c).into()
+    }
+}
+
+impl From<Rgb888> for WireRgb666 {
+    #[inline(always)]
+    fn from(c: Rgb888) -> Self {
+        Rgb666::from(c).into()
+    }
+}